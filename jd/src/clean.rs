@@ -0,0 +1,290 @@
+//! Finding and fixing problems in the directories of a `System`.
+
+use crate::sync::{find_dir, remove};
+use crate::{derive_path, persistence, Error, System, INDEX_FILE};
+use johnnydecimal::{Area, AreaNumber, Category, Entry, Id, Index};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A problem in the directories of a `System`, from `System::clean`.
+///
+/// Paths start from the root of the `System`, like `/10-19 Area/11 Category`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// An area, category, or id directory with nothing in it but hidden files.
+    Empty(String),
+
+    /// A directory that starts like an area, category, or id, but isn't a valid one.
+    Misnamed {
+        /// The path of the directory.
+        path: String,
+        /// Why it isn't valid.
+        error: johnnydecimal::Error,
+    },
+
+    /// A regular file in an area or category directory, outside of any id.
+    Stray(String),
+
+    /// A category directory in the directory of another area.
+    Misplaced {
+        /// The path of the category.
+        path: String,
+        /// The area the category belongs in.
+        area: AreaNumber,
+    },
+}
+
+/// Options for `System::clean`.
+///
+/// By default, problems are only found, not fixed, and entries of trashed directories are
+/// removed from `00.00 Index.txt` if it exists.
+///
+/// # Example
+///
+/// ```
+/// use jd::{CleanOptions, System};
+///
+/// let options = CleanOptions::new().with_trash_empty(false);
+///
+/// assert!(System::clean("../tests/simple", &options).is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanOptions {
+    trash_empty: bool,
+    inbox: Option<String>,
+    index_file: bool,
+}
+
+impl Default for CleanOptions {
+    fn default() -> Self {
+        Self {
+            trash_empty: false,
+            inbox: None,
+            index_file: true,
+        }
+    }
+}
+
+impl CleanOptions {
+    /// Creates the default `CleanOptions`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move empty area, category, and id directories to the Trash.
+    pub fn with_trash_empty(mut self, trash_empty: bool) -> Self {
+        self.trash_empty = trash_empty;
+        self
+    }
+
+    /// Move stray files into the directory of the id with a given number, like `00.01`.
+    pub fn with_inbox(mut self, inbox: &str) -> Self {
+        self.inbox = Some(inbox.to_string());
+        self
+    }
+
+    /// Keep `00.00 Index.txt` in sync with trashed directories, or never read or write it.
+    pub fn with_index_file(mut self, index_file: bool) -> Self {
+        self.index_file = index_file;
+        self
+    }
+}
+
+impl System {
+    /// Returns the problems in the directories in `root`, fixing those that `options` allow.
+    ///
+    /// Directories in `root` that don't start with a digit aren't checked, so a `System` can
+    /// share its root with other files. Trashed directories are removed from the index file,
+    /// keeping its formatting, so the `System` stays in sync. Only entries at the same path as the
+    /// trashed directory are removed, and empty directories of entries with children in the index
+    /// file aren't trashed. If successful, each problem is
+    /// returned as `Ok` with whether it was fixed.
+    pub fn clean(root: &str, options: &CleanOptions) -> Result<Vec<(Problem, bool)>, Error> {
+        let index_path = Path::new(root).join(INDEX_FILE);
+        let file = if options.index_file && options.trash_empty { fs::read_to_string(&index_path).ok() } else { None };
+        let mut index = file.as_deref().map(Index::new).transpose()?;
+
+        let inbox = match &options.inbox {
+            Some(inbox) => match inbox.parse::<Entry>()? {
+                entry @ Entry::Id(_) => match find_dir(Path::new(root), &entry) {
+                    Some(dir) => Some(dir),
+                    None => return Err(johnnydecimal::Error::NotFound(entry.get_number()).into()),
+                },
+                _ => return Err(Error::System("The inbox for stray files must be an id.")),
+            },
+            None => None,
+        };
+
+        let mut problems = vec![];
+
+        for (path, name) in read_dir(root, "")? {
+            if is_dir(root, &path) {
+                match Area::new(&name) {
+                    Ok(area) => check_area(root, &path, &area, &mut problems)?,
+                    Err(error) if looks_numbered(&name) => problems.push(Problem::Misnamed { path, error }),
+                    Err(_) => {}
+                }
+            }
+        }
+
+        // The inbox is often empty, and is kept for stray files.
+        problems.retain(|problem| !matches!((problem, &inbox), (Problem::Empty(path), Some(inbox)) if Path::new(&format!("{root}{path}")) == inbox));
+
+        let mut results = vec![];
+        let mut trashed = false;
+
+        for problem in problems {
+            let fixed = match &problem {
+                Problem::Empty(path) if options.trash_empty => {
+                    let entry = index.as_ref().and_then(|index| indexed(index, path));
+
+                    // The children of an entry in the index are missing, not unused, so its
+                    // directory is kept until the index file is synced.
+                    if index.as_ref().zip(entry.as_ref()).is_some_and(|(index, entry)| has_children(index, entry)) {
+                        false
+                    } else {
+                        let deleted = trash::delete(format!("{root}{path}")).is_ok();
+
+                        if let (true, Some(index), Some(entry)) = (deleted, index.as_mut(), entry) {
+                            remove(index, &entry)?;
+                            trashed = true;
+                        }
+
+                        deleted
+                    }
+                }
+                Problem::Stray(path) => inbox.as_ref().is_some_and(|inbox| {
+                    let to = inbox.join(Path::new(path).file_name().unwrap_or_default());
+
+                    !to.exists() && fs::rename(format!("{root}{path}"), to).is_ok()
+                }),
+                _ => false,
+            };
+
+            results.push((problem, fixed));
+        }
+
+        if let (true, Some(str), Some(index)) = (trashed, file, index) {
+            persistence::write(&index_path, &persistence::update(&str, &index))?;
+        }
+
+        Ok(results)
+    }
+}
+
+/// Finds the problems in the directory of an area and its categories.
+fn check_area(root: &str, path: &str, area: &Area, problems: &mut Vec<Problem>) -> Result<(), Error> {
+    let children = read_dir(root, path)?;
+
+    if children.is_empty() {
+        problems.push(Problem::Empty(path.to_string()));
+    }
+
+    for (path, name) in children {
+        if !is_dir(root, &path) {
+            problems.push(Problem::Stray(path));
+            continue;
+        }
+
+        match Category::new(&name) {
+            Ok(category) if category.get_area() != area.get_area() => problems.push(Problem::Misplaced {
+                path: path.clone(),
+                area: category.get_area(),
+            }),
+            Ok(_) => {}
+            Err(error) => {
+                if looks_numbered(&name) {
+                    problems.push(Problem::Misnamed { path, error });
+                }
+
+                continue;
+            }
+        }
+
+        check_category(root, &path, problems)?;
+    }
+
+    Ok(())
+}
+
+/// Finds the problems in the directory of a category and its ids.
+fn check_category(root: &str, path: &str, problems: &mut Vec<Problem>) -> Result<(), Error> {
+    let children = read_dir(root, path)?;
+
+    if children.is_empty() {
+        problems.push(Problem::Empty(path.to_string()));
+    }
+
+    for (path, name) in children {
+        if !is_dir(root, &path) {
+            problems.push(Problem::Stray(path));
+            continue;
+        }
+
+        match Id::new(&name) {
+            Ok(_) if read_dir(root, &path)?.is_empty() => problems.push(Problem::Empty(path)),
+            Ok(_) => {}
+            Err(error) if looks_numbered(&name) => problems.push(Problem::Misnamed { path, error }),
+            Err(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the paths and names of the visible files and directories in a directory, sorted by name.
+fn read_dir(root: &str, path: &str) -> Result<Vec<(String, String)>, Error> {
+    let Ok(directory) = fs::read_dir(format!("{root}{path}")) else {
+        return Err(Error::System("Couldn't read a directory to clean."));
+    };
+
+    let mut children: Vec<(String, String)> = directory
+        .flatten()
+        .filter_map(|child| child.file_name().into_string().ok())
+        .filter(|name| !name.starts_with('.'))
+        .map(|name| (format!("{path}/{name}"), name))
+        .collect();
+
+    children.sort();
+
+    Ok(children)
+}
+
+/// Returns the entry of a directory in an `Index`, if the index has it at the same path.
+fn indexed(index: &Index, path: &str) -> Option<Entry> {
+    let name = path.rsplit('/').next()?;
+    let entry = index.get_entry(&name.parse().ok()?)?;
+
+    (derive_path(index, &entry).ok()? == path).then_some(entry)
+}
+
+/// Returns true if an area or category has children in an `Index`.
+fn has_children(index: &Index, entry: &Entry) -> bool {
+    match entry {
+        Entry::Area(area) => !index.derive_categories_of_area(area).is_empty(),
+        Entry::Category(category) => !index.derive_ids_of_category(category).is_empty(),
+        Entry::Id(_) => false,
+    }
+}
+
+/// Returns true if a path from the root is a directory.
+fn is_dir(root: &str, path: &str) -> bool {
+    Path::new(&format!("{root}{path}")).is_dir()
+}
+
+/// Returns true if a name starts with a digit, like the numbers of areas, categories, and ids.
+fn looks_numbered(name: &str) -> bool {
+    name.starts_with(|char: char| char.is_ascii_digit())
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty(path) => write!(f, "Empty directory: {path}"),
+            Self::Misnamed { path, error } => write!(f, "Invalid name: {path}\n{error}"),
+            Self::Stray(path) => write!(f, "File outside of an id: {path}"),
+            Self::Misplaced { path, area } => write!(f, "Category in the wrong area: {path} belongs in {area}"),
+        }
+    }
+}
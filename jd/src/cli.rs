@@ -1,5 +1,5 @@
 use clap::builder::styling::{AnsiColor, Effects, Styles};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 const LONG_ABOUT: &str = "jd is a command line interface for interacting with Johnny Decimal systems.";
@@ -15,10 +15,18 @@ fn styles() -> Styles {
 #[derive(Parser)]
 #[command(author, version, about, long_about = LONG_ABOUT, styles = styles())]
 pub struct Cli {
-    /// Directory where areas are stored.
+    /// Directory where areas are stored. Defaults to `JD_ROOT`, the config file, or `$HOME`.
     #[arg(short, long)]
     pub dir: Option<PathBuf>,
 
+    /// Named system from the config file to use instead of the default.
+    #[arg(short, long)]
+    pub system: Option<String>,
+
+    /// Drive the system only by its directories, without reading or writing `00.00 Index.txt`.
+    #[arg(long, default_value_t = false)]
+    pub no_index_file: bool,
+
     /// Print debugging information
     #[arg(short, long, default_value_t = false)]
     pub verbose: bool,
@@ -30,25 +38,129 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Add a new area, category, or id to the filesystem.
-    Add { name: Vec<String> },
+    ///
+    /// Use `--next` with a category or area number and a title, like `jd add --next 11 Title`, to
+    /// use its next free id or category.
+    Add {
+        /// Use the next free id of a category, or the next free category of an area.
+        #[arg(short, long)]
+        next: bool,
+
+        name: Vec<String>,
+    },
 
     /// Remove an existing area, category, or id from the filesystem.
+    ///
+    /// Give only its number, like `jd rm 11.01`, to remove it without typing its title.
     Rm { name: Vec<String> },
 
+    /// Move an id to another category, giving it the next free number there.
+    ///
+    /// For example, `jd mv 11.03 22` moves `11.03` to the lowest free id of `22`.
+    Mv {
+        /// The id to move.
+        id: String,
+
+        /// The category to move it to.
+        category: String,
+    },
+
+    /// Change the title of an area, category, or id.
+    ///
+    /// For example, `jd rename 11.01 "New title"`.
+    Rename {
+        /// The number of the area, category, or id.
+        number: String,
+
+        /// The new title.
+        title: Vec<String>,
+    },
+
+    /// Compare `00.00 Index.txt` with the directories, and resolve their differences.
+    ///
+    /// Without options, only the differences are printed.
+    Sync {
+        /// Change the index file to match the directories.
+        #[arg(long, conflicts_with_all = ["from_index", "interactive"])]
+        from_fs: bool,
+
+        /// Create and rename directories to match the index file, leaving extra ones alone.
+        #[arg(long, conflicts_with = "interactive")]
+        from_index: bool,
+
+        /// Decide which side to keep for each difference, one at a time.
+        #[arg(short, long)]
+        interactive: bool,
+    },
+
     /// List the contents of the index
     Index {},
 
-    /// Insert a new area, category, or id, moving existing ones.
+    /// Export the index as a table or outline.
+    Export {
+        /// Format to export.
+        #[arg(short, long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+    },
+
+    /// Import an index from a table or outline, reporting where problems are.
     ///
-    /// NOTE: This is non-trivial to implement since it cascades.
-    // Insert { name: String },
+    /// The index is printed, not written, so it can be checked or redirected to `00.00 Index.txt`.
+    Import {
+        /// File to import.
+        file: PathBuf,
 
-    /// Checks for empty folders and missing areas/categories/ids (Prune/Clean)
+        /// Format of the file. Defaults to the extension of the file.
+        #[arg(short, long, value_enum)]
+        format: Option<Format>,
+    },
+
+    /// Insert a new category or id, moving existing ones.
     ///
-    /// NOTE: Could simplify this to a "check" function that validates the index with
-    /// the filesystem (performance)
-    Clean {},
+    /// Later categories or ids are shifted up by one until a free number, like `11.03` to `11.04`.
+    Insert { name: Vec<String> },
+
+    /// Find empty folders, invalid names, files outside of ids, and categories in the wrong area.
+    Clean {
+        /// Move empty area, category, and id folders to the Trash.
+        #[arg(long)]
+        fix_empty: bool,
+
+        /// Move files outside of ids into the id with this number, like `00.01`.
+        #[arg(long, value_name = "INBOX")]
+        fix_stray: Option<String>,
+    },
 
     /// Start a new web server.
     Serve {},
+
+    /// Start a Language Server for index files over stdio.
+    Lsp {},
+
+    /// Merge index files as a git merge driver, writing the result to `ours`.
+    ///
+    /// Exits with 1 if there are conflicts, which are marked with `// Conflict:` comments.
+    MergeDriver {
+        /// The common ancestor, `%O`.
+        base: PathBuf,
+
+        /// Our version, `%A`, which is replaced with the result.
+        ours: PathBuf,
+
+        /// Their version, `%B`.
+        theirs: PathBuf,
+    },
+}
+
+/// A format that indexes can be exported to and imported from.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Comma-separated values.
+    Csv,
+
+    /// Tab-separated values.
+    Tsv,
+
+    /// Outlines for outliner apps.
+    Opml,
 }
@@ -0,0 +1,153 @@
+//! Choosing the root of a `System` from options, the environment, and a config file.
+
+use crate::Error;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `config.toml` in the XDG config directory, like `~/.config/jd/config.toml`.
+///
+/// `root` is the default system. Each table in `systems` is a named system with its own `root`.
+/// A leading `~` in a root is the home directory.
+///
+/// # Example
+///
+/// ```
+/// use jd::Config;
+/// use std::path::Path;
+///
+/// let config = Config::new(r#"
+/// root = "/home/user/Documents"
+///
+/// [systems.work]
+/// root = "/home/user/Work"
+/// "#).expect("Invalid config");
+///
+/// assert_eq!(config.get_root(), Some(Path::new("/home/user/Documents")));
+/// assert_eq!(config.get_system("work"), Some(Path::new("/home/user/Work")));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    root: Option<PathBuf>,
+    systems: BTreeMap<String, PathBuf>,
+}
+
+impl Config {
+    /// Creates a new `Config` from the contents of a config file.
+    pub fn new(str: &str) -> Result<Self, Error> {
+        let table: toml::Table = str.parse().map_err(|error: toml::de::Error| Error::Config(error.message().to_string()))?;
+        let mut config = Self::default();
+
+        for (key, value) in &table {
+            match (key.as_str(), value) {
+                ("root", toml::Value::String(root)) => config.root = Some(PathBuf::from(root)),
+                ("systems", toml::Value::Table(systems)) => {
+                    for (name, system) in systems {
+                        let Some(root) = system.get("root").and_then(toml::Value::as_str) else {
+                            return Err(Error::Config(format!("System `{name}` needs a `root` string")));
+                        };
+
+                        config.systems.insert(name.to_string(), PathBuf::from(root));
+                    }
+                }
+                ("root", _) => return Err(Error::Config("`root` must be a string".to_string())),
+                ("systems", _) => return Err(Error::Config("`systems` must be a table of named systems".to_string())),
+                (key, _) => return Err(Error::Config(format!("Unknown key `{key}`"))),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Reads the `Config` from `config.toml` in the XDG config directory, if it exists.
+    pub fn load() -> Result<Option<Self>, Error> {
+        let Some(path) = config_path(|key| std::env::var(key).ok()) else {
+            return Ok(None);
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(str) => Self::new(&str).map(Some),
+            Err(_) if !path.exists() => Ok(None),
+            Err(_) => Err(Error::System("Couldn't read the config file")),
+        }
+    }
+
+    /// Returns the root of the default system, if any.
+    pub fn get_root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Returns the root of the system with a given name, if it exists.
+    pub fn get_system(&self, name: &str) -> Option<&Path> {
+        self.systems.get(name).map(PathBuf::as_path)
+    }
+}
+
+/// Returns the path of `config.toml`, which is `$XDG_CONFIG_HOME/jd/config.toml` or
+/// `$HOME/.config/jd/config.toml`.
+///
+/// Environment variables are read with `var`, like `|key| std::env::var(key).ok()`.
+pub fn config_path(var: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let config = var("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    let config = config.or_else(|| var("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(config.join("jd").join("config.toml"))
+}
+
+/// Returns the root of a `System`.
+///
+/// The first of these is used:
+///
+/// 1. A directory like `--dir`
+/// 2. A named system in the config like `--system`, which must exist
+/// 3. The `JD_ROOT` environment variable
+/// 4. The `root` in the config
+/// 5. The `HOME` environment variable
+///
+/// Environment variables are read with `var`, like `|key| std::env::var(key).ok()`.
+///
+/// # Example
+///
+/// ```
+/// use jd::{config, Config};
+/// use std::path::Path;
+///
+/// let config = Config::new("[systems.work]\nroot = \"~/Work\"").expect("Invalid config");
+/// let var = |key: &str| (key == "HOME").then(|| "/home/user".to_string());
+///
+/// assert_eq!(config::find_root(None, Some("work"), Some(&config), var), Ok(Path::new("/home/user/Work").to_path_buf()));
+/// assert_eq!(config::find_root(None, None, Some(&config), var), Ok(Path::new("/home/user").to_path_buf()));
+/// ```
+pub fn find_root(dir: Option<&Path>, system: Option<&str>, config: Option<&Config>, var: impl Fn(&str) -> Option<String>) -> Result<PathBuf, Error> {
+    if let Some(dir) = dir {
+        return Ok(dir.to_path_buf());
+    }
+
+    let home = var("HOME");
+
+    if let Some(name) = system {
+        let Some(root) = config.and_then(|config| config.get_system(name)) else {
+            return Err(Error::Config(format!("System `{name}` isn't in the config file")));
+        };
+
+        return Ok(expand_home(root, home.as_deref()));
+    }
+
+    if let Some(root) = var("JD_ROOT").filter(|root| !root.is_empty()) {
+        return Ok(PathBuf::from(root));
+    }
+
+    if let Some(root) = config.and_then(Config::get_root) {
+        return Ok(expand_home(root, home.as_deref()));
+    }
+
+    home.map(PathBuf::from).ok_or(Error::System("Couldn't find a root. Use --dir, JD_ROOT, or a config file."))
+}
+
+/// Replaces a leading `~` in a path with the home directory.
+fn expand_home(path: &Path, home: Option<&str>) -> PathBuf {
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) => Path::new(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
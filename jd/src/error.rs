@@ -0,0 +1,45 @@
+//! Errors returned when reading and changing a `System`.
+
+use std::fmt;
+
+/// An error returned by a `System`.
+///
+/// The `Display` implementation is a friendly message you can show to your users.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The `Index` of the `System` couldn't be parsed or changed.
+    Index(johnnydecimal::Error),
+    /// The `System` or its directories couldn't be read or changed.
+    System(&'static str),
+    /// The index file doesn't match the directories of the `System`.
+    OutOfSync(johnnydecimal::Diff),
+    /// The config file or a named system in it couldn't be used.
+    Config(String),
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Index(error) => Some(error),
+            Self::System(_) | Self::OutOfSync(_) | Self::Config(_) => None,
+        }
+    }
+}
+
+impl From<johnnydecimal::Error> for Error {
+    fn from(error: johnnydecimal::Error) -> Self {
+        Self::Index(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(error) => write!(f, "{error}"),
+            Self::System(message) => write!(f, "{message}"),
+            Self::OutOfSync(diff) => write!(f, "Filesystem and index file are different:\n{diff}"),
+            Self::Config(message) => write!(f, "Invalid config: {message}"),
+        }
+    }
+}
@@ -1,55 +1,136 @@
 #![doc = include_str!("../README.md")]
 
-use johnnydecimal::{Area, Category, Id, Index};
+use johnnydecimal::{Area, Category, Entry, Id, Index};
 use std::fs;
+use std::path::Path;
+
+mod clean;
+pub mod config;
+mod error;
+pub mod lsp;
+mod persistence;
+mod sync;
+
+pub use clean::{CleanOptions, Problem};
+pub use config::Config;
+pub use error::Error;
+pub use sync::Source;
+
+/// The name of the index file in the root of a `System`.
+const INDEX_FILE: &str = "00.00 Index.txt";
 
 /// A `System` consists of a `root` and an `index`.
 #[derive(Debug)]
 pub struct System {
     root: String,
     index: Index,
+    index_file: bool,
+}
+
+/// Options for `System::with_options`.
+///
+/// By default, a `System` keeps `00.00 Index.txt` in its root, creating it on the first change
+/// if it doesn't exist yet.
+///
+/// # Example
+///
+/// ```
+/// use jd::{System, SystemOptions};
+///
+/// let options = SystemOptions::new().with_index_file(false);
+///
+/// assert!(System::with_options("../tests/simple", &options).is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemOptions {
+    index_file: bool,
+}
+
+impl Default for SystemOptions {
+    fn default() -> Self {
+        Self { index_file: true }
+    }
+}
+
+impl SystemOptions {
+    /// Creates the default `SystemOptions`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `00.00 Index.txt` in sync with every change, or drive the `System` only by its
+    /// directories, ignoring and never writing an index file.
+    pub fn with_index_file(mut self, index_file: bool) -> Self {
+        self.index_file = index_file;
+        self
+    }
 }
 
 impl System {
-    /// Creates a new `System` from a given `root`.
+    /// Creates a new `System` from a given `root` with the default `SystemOptions`.
     ///
     /// A `System` is an `Index` instantiated from a given `root` on a filesystem. Put simply, it's
     /// the glue between the theoretical concept of an `Index` and its implementation on the
     /// filesystem.
-    pub fn new(root: &str) -> Result<Self, &'static str> {
-        if let Ok(string) = fs::read_to_string(format!("{root}/00.00 Index.txt")) {
-            if let Ok(index) = Index::new(&string) {
+    ///
+    /// If `00.00 Index.txt` exists in `root`, it must be a valid `Index`, otherwise an
+    /// `Error::Index` with the line that failed is returned. It must also match the directories
+    /// in `root`, otherwise an `Error::OutOfSync` with their differences is returned. Without an index file, the `Index` is
+    /// derived from the directories in `root`.
+    ///
+    /// Every change to the `System` rewrites the index file atomically, creating it if needed.
+    pub fn new(root: &str) -> Result<Self, Error> {
+        Self::with_options(root, &SystemOptions::default())
+    }
+
+    /// Creates a new `System` from a given `root` with given `SystemOptions`.
+    pub fn with_options(root: &str, options: &SystemOptions) -> Result<Self, Error> {
+        let system = |index| Self {
+            root: root.to_string(),
+            index,
+            index_file: options.index_file,
+        };
+
+        if options.index_file {
+            if let Ok(string) = fs::read_to_string(Path::new(root).join(INDEX_FILE)) {
+                let index = Index::new(&string)?;
+
                 if let Ok(index_fs) = get_index_from_fs(root) {
-                    if index != index_fs {
-                        return Err("Filesystem and index file are different");
+                    let diff = index.diff(&index_fs);
+
+                    if !diff.is_empty() {
+                        return Err(Error::OutOfSync(diff));
                     }
                 }
 
-                return Ok(Self { root: root.to_string(), index });
+                return Ok(system(index));
             }
         }
 
         if let Ok(index) = get_index_from_fs(root) {
-            return Ok(Self { root: root.to_string(), index });
+            return Ok(system(index));
         }
 
-        Err("Couldn't get index from file or directory contents")
+        Err(Error::System("Couldn't get index from file or directory contents"))
     }
 
     /// Adds a new `Area` to the `System`'s `Index`.
     ///
     /// If the area already exists in the cached index, the file won't be created.
-    pub fn add_area(&mut self, area: &Area) -> Result<&Vec<Area>, &'static str> {
-        if self.index.get_areas().contains(area) {
-            return Err("Area already exists in index.");
+    pub fn add_area(&mut self, area: &Area) -> Result<Vec<&Area>, Error> {
+        if self.index.get_area(area.get_area()).is_some() {
+            return Err(Error::System("Area already exists in index."));
         }
 
         let path = self.index.derive_path_for_area(area)?;
 
         if fs::create_dir(self.root.clone() + &path).is_ok() {
-            self.index.add_area(area)
+            self.index.add_area(area)?;
+            self.save_index()?;
+
+            Ok(self.index.get_areas().collect())
         } else {
-            Err("A directory for the given area already exists, but wasn't in index.")
+            Err(Error::System("A directory for the given area already exists, but wasn't in index."))
         }
     }
 
@@ -58,9 +139,9 @@ impl System {
     /// This operation moves a user's given `Area` to the user's Trash directory.
     ///
     /// Also removes child categories and grandchild ids from the `Index`.
-    pub fn remove_area(&mut self, area: &Area) -> Result<&Vec<Area>, &'static str> {
-        if !self.index.get_areas().contains(area) {
-            todo!("Handle possibility that filesystem could have area but index doesn't")
+    pub fn remove_area(&mut self, area: &Area) -> Result<Vec<&Area>, Error> {
+        if self.index.get_area(area.get_area()).is_none() {
+            return Err(johnnydecimal::Error::NotFound(area.get_area().to_string()).into());
         }
 
         let path = self.index.derive_path_for_area(area)?;
@@ -73,35 +154,41 @@ impl System {
 
                 for id in ids {
                     if self.index.remove_id(&id).is_err() {
-                        return Err("Error removing id from index");
+                        return Err(Error::System("Error removing id from index"));
                     }
                 }
 
                 if self.index.remove_category(&category).is_err() {
-                    return Err("Error removing category from index");
+                    return Err(Error::System("Error removing category from index"));
                 }
             }
 
-            self.index.remove_area(area)
+            self.index.remove_area(area)?;
+            self.save_index()?;
+
+            Ok(self.index.get_areas().collect())
         } else {
-            Err("The given area *was* in the index, but *wasn't* able to be moved to trash.")
+            Err(Error::System("The given area *was* in the index, but *wasn't* able to be moved to trash."))
         }
     }
 
     /// Adds a new `Category` to the `System`'s `Index`.
     ///
     /// If the category already exists in the cached index, the file won't be created.
-    pub fn add_category(&mut self, category: &Category) -> Result<&Vec<Category>, &'static str> {
-        if self.index.get_categories().contains(category) {
-            return Err("Category already exists in index.");
+    pub fn add_category(&mut self, category: &Category) -> Result<Vec<&Category>, Error> {
+        if self.index.get_category(category.get_category()).is_some() {
+            return Err(Error::System("Category already exists in index."));
         }
 
         let path = self.index.derive_path_for_category(category)?;
 
         if fs::create_dir(self.root.clone() + &path).is_ok() {
-            self.index.add_category(category)
+            self.index.add_category(category)?;
+            self.save_index()?;
+
+            Ok(self.index.get_categories().collect())
         } else {
-            Err("A directory for the given category already exists, but wasn't in index.")
+            Err(Error::System("A directory for the given category already exists, but wasn't in index."))
         }
     }
 
@@ -110,9 +197,9 @@ impl System {
     /// This operation moves a user's given `Category` to the user's Trash directory.
     ///
     /// Also removes child ids from the `Index`.
-    pub fn remove_category(&mut self, category: &Category) -> Result<&Vec<Category>, &'static str> {
-        if !self.index.get_categories().contains(category) {
-            todo!("Handle possibility that filesystem could have category but index doesn't")
+    pub fn remove_category(&mut self, category: &Category) -> Result<Vec<&Category>, Error> {
+        if self.index.get_category(category.get_category()).is_none() {
+            return Err(johnnydecimal::Error::NotFound(category.get_category().to_string()).into());
         }
 
         let path = self.index.derive_path_for_category(category)?;
@@ -122,66 +209,273 @@ impl System {
 
             for id in ids {
                 if self.index.remove_id(&id).is_err() {
-                    return Err("Error removing id from index");
+                    return Err(Error::System("Error removing id from index"));
                 }
             }
 
-            self.index.remove_category(category)
+            self.index.remove_category(category)?;
+            self.save_index()?;
+
+            Ok(self.index.get_categories().collect())
         } else {
-            Err("The given category *was* in the index, but *wasn't* able to be moved to trash.")
+            Err(Error::System("The given category *was* in the index, but *wasn't* able to be moved to trash."))
         }
     }
 
     /// Adds a new `Id` to the `System`'s `Index`.
     ///
     /// If the id already exists in the cached index, the file won't be created.
-    pub fn add_id(&mut self, id: &Id) -> Result<&Vec<Id>, &'static str> {
-        if self.index.get_ids().contains(id) {
-            return Err("Id already exists in index.");
+    pub fn add_id(&mut self, id: &Id) -> Result<Vec<&Id>, Error> {
+        if self.index.get_id(id.get_id()).is_some() {
+            return Err(Error::System("Id already exists in index."));
         }
 
         let path = self.index.derive_path_for_id(id)?;
 
         if fs::create_dir(self.root.clone() + &path).is_ok() {
-            self.index.add_id(id)
+            self.index.add_id(id)?;
+            self.save_index()?;
+
+            Ok(self.index.get_ids().collect())
         } else {
-            Err("A directory for the given id already exists, but wasn't in index.")
+            Err(Error::System("A directory for the given id already exists, but wasn't in index."))
         }
     }
 
     /// Removes an existing `Id` from the `System`'s `Index`.
-    pub fn remove_id(&mut self, id: &Id) -> Result<&Vec<Id>, &'static str> {
-        if !self.index.get_ids().contains(id) {
-            todo!("Handle possibility that filesystem could have category but index doesn't")
+    pub fn remove_id(&mut self, id: &Id) -> Result<Vec<&Id>, Error> {
+        if self.index.get_id(id.get_id()).is_none() {
+            return Err(johnnydecimal::Error::NotFound(id.get_id().to_string()).into());
         }
 
         let path = self.index.derive_path_for_id(id)?;
 
         if trash::delete(self.root.clone() + &path).is_ok() {
-            self.index.remove_id(id)
+            self.index.remove_id(id)?;
+            self.save_index()?;
+
+            Ok(self.index.get_ids().collect())
         } else {
-            Err("The given id *was* in the index, but *wasn't* able to be moved to trash.")
+            Err(Error::System("The given id *was* in the index, but *wasn't* able to be moved to trash."))
+        }
+    }
+
+    /// Moves an existing `Id` to another `Category`, giving it the next free number there.
+    ///
+    /// The directory of the id is renamed, and `00.00 Index.txt` is updated if the `System` has
+    /// one. If successful, the old and new paths of the id are returned as `Ok`.
+    pub fn move_id(&mut self, id: &Id, category: &Category) -> Result<(String, String), Error> {
+        let Some(id) = self.index.get_id(id.get_id()).cloned() else {
+            return Err(Error::System("Id doesn't exist in index."));
+        };
+
+        let Some(category) = self.index.get_category(category.get_category()).cloned() else {
+            return Err(Error::System("Category doesn't exist in index."));
+        };
+
+        let Some(number) = self.index.next_free_id(&category) else {
+            return Err(johnnydecimal::Error::Full(category.get_category().to_string()).into());
+        };
+
+        let from = self.index.derive_path_for_id(&id)?;
+        let to = self.index.derive_path_for_id(&Id::new(&format!("{number} {}", id.get_name()))?)?;
+
+        if Path::new(&(self.root.clone() + &to)).exists() {
+            return Err(Error::System("A directory for the moved id already exists, but wasn't in index."));
+        }
+
+        if fs::rename(self.root.clone() + &from, self.root.clone() + &to).is_err() {
+            return Err(Error::System("The given id *was* in the index, but *wasn't* able to be moved."));
+        }
+
+        self.index.move_id(&id, &category)?;
+        self.save_index()?;
+
+        Ok((from, to))
+    }
+
+    /// Changes the title of the area, category, or id with a given number like `11.01`.
+    ///
+    /// The directory of the entry is renamed, and `00.00 Index.txt` is updated if the `System`
+    /// has one. Every other entry is kept. If successful, the old and new paths of the entry are
+    /// returned as `Ok`.
+    pub fn rename(&mut self, number: &str, title: &str) -> Result<(String, String), Error> {
+        let mut index = self.index.clone();
+        let renamed = index.rename(number, title)?;
+
+        let from = match self.index.get_entry(&renamed) {
+            Some(entry) => derive_path(&self.index, &entry)?,
+            None => return Err(Error::System("Entry doesn't exist in index.")),
+        };
+
+        let to = derive_path(&index, &renamed)?;
+
+        if from == to {
+            return Ok((from, to));
+        }
+
+        if Path::new(&(self.root.clone() + &to)).exists() {
+            return Err(Error::System("A directory with the new title already exists, but wasn't in index."));
+        }
+
+        if fs::rename(self.root.clone() + &from, self.root.clone() + &to).is_err() {
+            return Err(Error::System("The given entry *was* in the index, but *wasn't* able to be renamed."));
         }
+
+        self.index = index;
+        self.save_index()?;
+
+        Ok((from, to))
+    }
+
+    /// Inserts a new `Id` at its number, shifting later ids of its category up by one to make room.
+    ///
+    /// The directories of shifted ids are renamed from the highest number down, so none is
+    /// clobbered, and then the directory of the new id is created. Nothing changes if the shift
+    /// would go past `.99` or a directory to shift to already exists. If successful, the shifted ids
+    /// are returned as `Ok` pairs of before and after.
+    pub fn insert_id_at(&mut self, id: &Id) -> Result<Vec<(Id, Id)>, Error> {
+        let mut index = self.index.clone();
+        let shifted = index.insert_id_at(id)?;
+        let mut renames = vec![];
+
+        for (from, to) in &shifted {
+            renames.push((self.index.derive_path_for_id(from)?, index.derive_path_for_id(to)?));
+        }
+
+        self.shift(&renames, &index.derive_path_for_id(id)?)?;
+        self.index = index;
+        self.save_index()?;
+
+        Ok(shifted)
+    }
+
+    /// Inserts a new `Category` at its number, shifting later categories of its area up by one to
+    /// make room.
+    ///
+    /// Like `System::insert_id_at`, except that the directories of the ids in each shifted
+    /// category are renamed too, since their numbers start with the number of the category.
+    pub fn insert_category_at(&mut self, category: &Category) -> Result<Vec<(Category, Category)>, Error> {
+        let mut index = self.index.clone();
+        let shifted = index.insert_category_at(category)?;
+        let mut renames = vec![];
+
+        for (from, to) in &shifted {
+            let path = index.derive_path_for_category(to)?;
+
+            renames.push((self.index.derive_path_for_category(from)?, path.clone()));
+
+            for (old, new) in self.index.derive_ids_of_category(from).iter().zip(index.derive_ids_of_category(to)) {
+                renames.push((format!("{path}/{old}"), format!("{path}/{new}")));
+            }
+        }
+
+        self.shift(&renames, &index.derive_path_for_category(category)?)?;
+        self.index = index;
+        self.save_index()?;
+
+        Ok(shifted)
     }
 
     /// Returns the current `Index` of the `System`.
     pub fn get_index(&self) -> &Index {
         &self.index
     }
+
+    /// Renames directories in order and then creates a new one, failing before any change if a
+    /// directory would be clobbered.
+    ///
+    /// If a rename or creating the directory fails, the renames already done are undone.
+    fn shift(&self, renames: &[(String, String)], path: &str) -> Result<(), Error> {
+        let clobbers = renames.iter().enumerate().any(|(i, (_, to))| self.exists_after(to, &renames[..i]));
+
+        if clobbers || self.exists_after(path, renames) {
+            return Err(Error::System("A directory to shift to already exists, but wasn't in index."));
+        }
+
+        for (i, (from, to)) in renames.iter().enumerate() {
+            if fs::rename(self.root.clone() + from, self.root.clone() + to).is_err() {
+                self.undo(&renames[..i]);
+
+                return Err(Error::System("A directory couldn't be shifted to make room."));
+            }
+        }
+
+        if fs::create_dir(self.root.clone() + path).is_err() {
+            self.undo(renames);
+
+            return Err(Error::System("The directory for the inserted entry couldn't be created."));
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if a directory would exist after some renames, which haven't happened yet.
+    fn exists_after(&self, path: &str, renames: &[(String, String)]) -> bool {
+        let mut path = path.to_string();
+
+        for (from, to) in renames.iter().rev() {
+            if let Some(rest) = strip_dir(&path, to) {
+                path = format!("{from}{rest}");
+            } else if strip_dir(&path, from).is_some() {
+                return false;
+            }
+        }
+
+        Path::new(&(self.root.clone() + &path)).exists()
+    }
+
+    /// Undoes renames from the last to the first.
+    fn undo(&self, renames: &[(String, String)]) {
+        for (from, to) in renames.iter().rev() {
+            let _ = fs::rename(self.root.clone() + to, self.root.clone() + from);
+        }
+    }
+
+    /// Writes the `Index` to `00.00 Index.txt` atomically, keeping the formatting of the existing
+    /// file, unless the `System` is driven only by its directories.
+    fn save_index(&self) -> Result<(), Error> {
+        if !self.index_file {
+            return Ok(());
+        }
+
+        let path = Path::new(&self.root).join(INDEX_FILE);
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(str) => persistence::update(&str, &self.index),
+            Err(_) => format!("{}\n", self.index),
+        };
+
+        persistence::write(&path, &contents)
+    }
 }
 
-fn get_index_from_fs(root: &str) -> Result<Index, &'static str> {
+/// Returns the rest of a path inside a given directory, like `/11.01 Id` for `/11 Category`.
+fn strip_dir<'a>(path: &'a str, dir: &str) -> Option<&'a str> {
+    path.strip_prefix(dir).filter(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Returns the path of an area, category, or id in a given `Index`.
+fn derive_path(index: &Index, entry: &Entry) -> Result<String, Error> {
+    Ok(match entry {
+        Entry::Area(area) => index.derive_path_for_area(area)?,
+        Entry::Category(category) => index.derive_path_for_category(category)?,
+        Entry::Id(id) => index.derive_path_for_id(id)?,
+    })
+}
+
+fn get_index_from_fs(root: &str) -> Result<Index, Error> {
     let mut areas = vec![];
     let mut categories = vec![];
     let mut ids = vec![];
 
     let Ok(directory) = fs::read_dir(root) else {
-        return Err("Couldn't read root directory");
+        return Err(Error::System("Couldn't read root directory"));
     };
 
     for path in directory {
         let Ok(path) = path else {
-            return Err("Couldn't path in path");
+            return Err(Error::System("Couldn't path in path"));
         };
 
         if !path.path().is_dir() {
@@ -191,24 +485,24 @@ fn get_index_from_fs(root: &str) -> Result<Index, &'static str> {
         let maybe_area = path.file_name();
 
         let Some(maybe_area) = maybe_area.to_str() else {
-            return Err("Couldn't convert path to str");
+            return Err(Error::System("Couldn't convert path to str"));
         };
 
         if let Ok(area) = Area::new(maybe_area) {
             if areas.contains(&area) {
-                return Err("Given area is already in index");
+                return Err(Error::System("Given area is already in index"));
             }
 
             areas.push(area);
         }
 
         let Ok(subdirs) = fs::read_dir(path.path()) else {
-            return Err("Couldn't read subdirs");
+            return Err(Error::System("Couldn't read subdirs"));
         };
 
         for dir in subdirs {
             let Ok(dir) = dir else {
-                return Err("Couldn't dir subdir");
+                return Err(Error::System("Couldn't dir subdir"));
             };
 
             if !dir.path().is_dir() {
@@ -218,24 +512,24 @@ fn get_index_from_fs(root: &str) -> Result<Index, &'static str> {
             let maybe_category = dir.file_name();
 
             let Some(maybe_category) = maybe_category.to_str() else {
-                return Err("Couldn't convert path to str");
+                return Err(Error::System("Couldn't convert path to str"));
             };
 
             if let Ok(category) = Category::new(maybe_category) {
                 if categories.contains(&category) {
-                    return Err("Given category is already in index");
+                    return Err(Error::System("Given category is already in index"));
                 }
 
                 categories.push(category);
             }
 
             let Ok(sub_dirs) = fs::read_dir(dir.path()) else {
-                return Err("Couldn't read_dir of child");
+                return Err(Error::System("Couldn't read_dir of child"));
             };
 
             for sub_dir in sub_dirs {
                 let Ok(sub_dir) = sub_dir else {
-                    return Err("Couldn't dir sub_dir");
+                    return Err(Error::System("Couldn't dir sub_dir"));
                 };
 
                 if !sub_dir.path().is_dir() {
@@ -245,12 +539,12 @@ fn get_index_from_fs(root: &str) -> Result<Index, &'static str> {
                 let maybe_id = sub_dir.file_name();
 
                 let Some(maybe_id) = maybe_id.to_str() else {
-                    return Err("Couldn't convert path to str");
+                    return Err(Error::System("Couldn't convert path to str"));
                 };
 
                 if let Ok(id) = Id::new(maybe_id) {
                     if ids.contains(&id) {
-                        return Err("Given id is already in index");
+                        return Err(Error::System("Given id is already in index"));
                     }
 
                     ids.push(id)
@@ -259,9 +553,5 @@ fn get_index_from_fs(root: &str) -> Result<Index, &'static str> {
         }
     }
 
-    if let Ok(index) = Index::with_vecs(&areas, &categories, &ids) {
-        return Ok(index);
-    }
-
-    Err("An index couldn't be created")
+    Ok(Index::with_vecs(&areas, &categories, &ids)?)
 }
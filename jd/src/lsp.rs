@@ -0,0 +1,466 @@
+//! A Language Server for `00.00 Index.txt` files.
+//!
+//! The server speaks the [Language Server Protocol] over any reader and writer, usually stdin and
+//! stdout. It supports diagnostics, completion of the next free id, hover, go to definition, and
+//! document symbols.
+//!
+//! [Language Server Protocol]: https://microsoft.github.io/language-server-protocol/
+
+use johnnydecimal::{CategoryNumber, Document, Error, IdNumber, Index, NodeKind, Severity};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+/// The state of a running Language Server.
+///
+/// # Example
+///
+/// ```
+/// use jd::lsp::Server;
+/// use serde_json::json;
+///
+/// let mut server = Server::new();
+/// let responses = server.handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }));
+///
+/// assert_eq!(responses[0]["result"]["capabilities"]["hoverProvider"], true);
+/// ```
+#[derive(Debug, Default)]
+pub struct Server {
+    documents: HashMap<String, String>,
+    exited: bool,
+}
+
+/// Runs a Language Server until the client sends `exit` or closes the `reader`.
+pub fn run(mut reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+    let mut server = Server::new();
+
+    while let Some(content) = read_message(&mut reader)? {
+        let responses = match serde_json::from_slice::<Value>(&content) {
+            Ok(message) => server.handle(&message),
+            Err(_) => vec![error(Value::Null, -32700, "Given message is not valid JSON")],
+        };
+
+        for response in responses {
+            write_message(&mut writer, &response)?;
+        }
+
+        if server.is_exited() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+impl Server {
+    /// Creates a new `Server` without open documents.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` once the client has sent `exit`.
+    pub fn is_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Handles a JSON-RPC request or notification, returning the messages to send to the client.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        let Some(id) = message.get("id").cloned() else {
+            return self.notify(method, params);
+        };
+
+        let result = match method {
+            "initialize" => Some(capabilities()),
+            "shutdown" => Some(Value::Null),
+            "textDocument/completion" => self.with_document(params, completion),
+            "textDocument/hover" => self.with_document(params, hover),
+            "textDocument/definition" => self.with_document(params, definition),
+            "textDocument/documentSymbol" => self.with_document(params, symbols),
+            _ => None,
+        };
+
+        match result {
+            Some(result) => vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })],
+            None => vec![error(id, -32601, "Given method is not supported")],
+        }
+    }
+
+    /// Handles a notification, returning diagnostics if a document changed.
+    fn notify(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+
+                self.documents.insert(uri.clone(), text.to_string());
+            }
+
+            "textDocument/didChange" => {
+                let Some(text) = params["contentChanges"].as_array().and_then(|changes| changes.last()).and_then(|change| change["text"].as_str()) else {
+                    return vec![];
+                };
+
+                self.documents.insert(uri.clone(), text.to_string());
+            }
+
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+
+                return vec![publish(&uri, vec![])];
+            }
+
+            "exit" => {
+                self.exited = true;
+
+                return vec![];
+            }
+
+            _ => return vec![],
+        }
+
+        vec![publish(&uri, diagnostics(&self.documents[&uri]))]
+    }
+
+    /// Calls `f` with the text and position of the document in a request.
+    ///
+    /// Returns `null` if the document isn't open.
+    fn with_document(&self, params: &Value, f: fn(&str, &str, &Value) -> Value) -> Option<Value> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        Some(match self.documents.get(uri) {
+            Some(text) => f(uri, text, &params["position"]),
+            None => Value::Null,
+        })
+    }
+}
+
+/// Returns the capabilities of the server in response to `initialize`.
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": 1,
+            "completionProvider": { "triggerCharacters": ["."] },
+            "hoverProvider": true,
+            "definitionProvider": true,
+            "documentSymbolProvider": true,
+        },
+        "serverInfo": { "name": "jd", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// Returns every problem in a document as LSP diagnostics.
+fn diagnostics(text: &str) -> Vec<Value> {
+    let lines: Vec<&str> = text.lines().collect();
+
+    Index::validate(text)
+        .get_diagnostics()
+        .iter()
+        .map(|diagnostic| {
+            let location = match diagnostic.get_line() {
+                Some(line) => {
+                    let text = lines.get(line - 1).copied().unwrap_or_default();
+                    let column = match diagnostic.get_error() {
+                        Error::Parse(error) => error.get_column().saturating_sub(1),
+                        _ => 0,
+                    };
+                    let before: String = text.chars().take(column).collect();
+
+                    range(line - 1, width(&before), width(text))
+                }
+                // Problems without a line, like invalid metadata, apply to the whole document.
+                None => json!({
+                    "start": { "line": 0, "character": 0 },
+                    "end": { "line": lines.len().saturating_sub(1), "character": lines.last().map_or(0, |text| width(text)) },
+                }),
+            };
+            let severity = match diagnostic.get_severity() {
+                Severity::Error => 1,
+                Severity::Warning => 2,
+            };
+
+            json!({
+                "range": location,
+                "severity": severity,
+                "source": "jd",
+                "message": diagnostic.get_error().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Returns the next free id of the category at a position.
+///
+/// The category is taken from the number typed so far, or else from the nearest category above.
+fn completion(_uri: &str, text: &str, position: &Value) -> Value {
+    let Some((document, line, character)) = locate(text, position) else {
+        return json!([]);
+    };
+
+    let node_text = document.get_nodes().get(line).map_or("", |node| node.get_text());
+    let typed: String = node_text.chars().take(character).collect();
+    let indent = typed.len() - typed.trim_start().len();
+    let prefix = typed.trim_start();
+    let index = Index::validate(text).into_index();
+
+    let typed_category = prefix.strip_suffix('.').unwrap_or(prefix).parse::<CategoryNumber>().ok();
+    let category = typed_category.or_else(|| nearest_category(&document, line));
+
+    let Some(category) = category.and_then(|number| index.get_category(number).cloned()) else {
+        return json!([]);
+    };
+
+    let Some(id) = index.next_free_id(&category) else {
+        return json!([]);
+    };
+
+    json!([{
+        "label": id.to_string(),
+        "kind": 12,
+        "detail": format!("Next free id in {category}"),
+        "textEdit": {
+            "range": range(line, width(&typed[..indent]), width(&typed)),
+            "newText": format!("{id} "),
+        },
+    }])
+}
+
+/// Returns the parent area and category of the entry at a position.
+fn hover(_uri: &str, text: &str, position: &Value) -> Value {
+    let Some((document, line, _)) = locate(text, position) else {
+        return Value::Null;
+    };
+
+    let index = Index::validate(text).into_index();
+    let node = &document.get_nodes()[line];
+    let number = node.get_number().unwrap_or_default();
+
+    let parents = match node.get_kind() {
+        NodeKind::Id => {
+            let Some(id) = index.get_id(number.as_str()) else {
+                return Value::Null;
+            };
+
+            let (Ok(area), Ok(category)) = (index.get_area_from_id(id), index.get_category_from_id(id)) else {
+                return Value::Null;
+            };
+
+            vec![area.to_string(), category.to_string()]
+        }
+
+        NodeKind::Category => {
+            let Some(area) = index.get_category(number.as_str()).and_then(|c| index.get_area_from_category(c).ok()) else {
+                return Value::Null;
+            };
+
+            vec![area.to_string()]
+        }
+
+        _ => return Value::Null,
+    };
+
+    let value = parents.iter().map(|parent| format!("`{parent}`")).collect::<Vec<String>>().join(" > ");
+
+    json!({
+        "contents": { "kind": "markdown", "value": value },
+        "range": range(line, 0, width(node.get_text())),
+    })
+}
+
+/// Returns the line of the category of an id, or the area of a category, at a position.
+fn definition(uri: &str, text: &str, position: &Value) -> Value {
+    let Some((document, line, _)) = locate(text, position) else {
+        return Value::Null;
+    };
+
+    let node = &document.get_nodes()[line];
+
+    let (kind, parent) = match (node.get_kind(), node.get_number()) {
+        (NodeKind::Id, Some(number)) => (NodeKind::Category, number.parse::<IdNumber>().map(|id| id.get_category().to_string())),
+        (NodeKind::Category, Some(number)) => (NodeKind::Area, number.parse::<CategoryNumber>().map(|category| category.get_area().to_string())),
+        _ => return Value::Null,
+    };
+
+    let Ok(parent) = parent else {
+        return Value::Null;
+    };
+
+    let target = document.get_nodes().iter().position(|node| node.get_kind() == kind && node.get_number().as_ref() == Some(&parent));
+
+    match target {
+        Some(target) => json!({ "uri": uri, "range": range(target, 0, width(document.get_nodes()[target].get_text())) }),
+        None => Value::Null,
+    }
+}
+
+/// Returns the areas, categories, and ids of a document as a tree of symbols.
+fn symbols(_uri: &str, text: &str, _position: &Value) -> Value {
+    let Ok(document) = Document::new(text) else {
+        return json!([]);
+    };
+
+    let mut areas: Vec<(String, Value)> = vec![];
+    let mut categories: Vec<(String, Value)> = vec![];
+    let mut ids: Vec<(String, Value)> = vec![];
+
+    for (line, node) in document.get_nodes().iter().enumerate() {
+        let Some(number) = node.get_number() else {
+            continue;
+        };
+
+        let name = node.get_name().map_or(number.to_string(), |name| format!("{number} {name}"));
+        let line_range = range(line, 0, width(node.get_text()));
+        let (kind, list) = match node.get_kind() {
+            NodeKind::Area => (3, &mut areas),
+            NodeKind::Category => (2, &mut categories),
+            _ => (20, &mut ids),
+        };
+
+        list.push((
+            number.to_string(),
+            json!({ "name": name, "kind": kind, "range": line_range, "selectionRange": line_range, "children": [] }),
+        ));
+    }
+
+    let mut orphans = vec![];
+
+    for (number, symbol) in ids {
+        let parent = number.parse::<IdNumber>().map(|id| id.get_category().to_string()).unwrap_or_default();
+
+        match categories.iter_mut().find(|(category, _)| *category == parent) {
+            Some((_, parent)) => push_child(parent, symbol),
+            None => orphans.push(symbol),
+        }
+    }
+
+    for (number, symbol) in categories {
+        let parent = number.parse::<CategoryNumber>().map(|category| category.get_area().to_string()).unwrap_or_default();
+
+        match areas.iter_mut().find(|(area, _)| *area == parent) {
+            Some((_, parent)) => push_child(parent, symbol),
+            None => orphans.push(symbol),
+        }
+    }
+
+    let mut tree: Vec<Value> = areas.into_iter().map(|(_, symbol)| symbol).collect();
+
+    tree.extend(orphans);
+
+    Value::Array(tree)
+}
+
+/// Returns the document, line, and character of a position, if the position is in the document.
+fn locate(text: &str, position: &Value) -> Option<(Document, usize, usize)> {
+    let document = Document::new(text).ok()?;
+    let line = usize::try_from(position["line"].as_u64()?).ok()?;
+    let character = usize::try_from(position["character"].as_u64()?).ok()?;
+
+    if line >= document.get_nodes().len() {
+        return None;
+    }
+
+    let node_text = document.get_nodes()[line].get_text();
+    let mut units = 0;
+    let chars = node_text.chars().take_while(|c| {
+        units += c.len_utf16();
+
+        units <= character
+    });
+
+    let character = chars.count();
+
+    Some((document, line, character))
+}
+
+/// Returns the nearest category at or above a given line, stopping at areas.
+fn nearest_category(document: &Document, line: usize) -> Option<CategoryNumber> {
+    document.get_nodes()[..=line].iter().rev().find_map(|node| match (node.get_kind(), node.get_number()) {
+        (NodeKind::Category, Some(number)) => Some(number.parse::<CategoryNumber>().ok()),
+        (NodeKind::Id, Some(number)) => Some(number.parse::<IdNumber>().ok().map(IdNumber::get_category)),
+        (NodeKind::Area, _) => Some(None),
+        _ => None,
+    })?
+}
+
+/// Appends a child symbol to a parent symbol.
+fn push_child(parent: &mut Value, child: Value) {
+    if let Some(children) = parent["children"].as_array_mut() {
+        children.push(child);
+    }
+}
+
+/// Returns the width of some text in UTF-16 code units, as used by LSP positions.
+fn width(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Returns an LSP range on a single line.
+fn range(line: usize, start: usize, end: usize) -> Value {
+    json!({
+        "start": { "line": line, "character": start },
+        "end": { "line": line, "character": end },
+    })
+}
+
+/// Returns a `textDocument/publishDiagnostics` notification.
+fn publish(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Returns a JSON-RPC error response.
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Reads the content of a message framed by a `Content-Length` header.
+///
+/// Returns `None` if the reader is closed.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+
+    loop {
+        let mut header = String::new();
+
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let Some(length) = length else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Message does not have a Content-Length header"));
+    };
+
+    let mut content = vec![0; length];
+
+    reader.read_exact(&mut content)?;
+
+    Ok(Some(content))
+}
+
+/// Writes a message framed by a `Content-Length` header.
+fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+
+    write!(writer, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+
+    writer.flush()
+}
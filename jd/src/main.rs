@@ -1,27 +1,113 @@
 #![doc = include_str!("../README.md")]
 
 use clap::Parser;
-use cli::{Cli, Commands};
-use jd::System;
-use johnnydecimal::{Area, Category, Id};
+use cli::{Cli, Commands, Format};
+use jd::{config, CleanOptions, Config, Source, System, SystemOptions};
+use johnnydecimal::{AreaNumber, Category, CategoryNumber, Entry, Id, Index};
+use std::fmt::Display;
+use std::io::Write;
+use std::path::Path;
+use std::{env, fs, io, process};
 
 mod cli;
 
 fn main() {
     let cli = Cli::parse();
 
-    let Ok(mut system) = System::new("./tests/simple") else {
-        println!("Invalid system");
+    if let Some(Commands::Import { file, format }) = &cli.command {
+        let format = format.unwrap_or(match file.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("tsv") => Format::Tsv,
+            Some(extension) if extension.eq_ignore_ascii_case("opml") => Format::Opml,
+            _ => Format::Csv,
+        });
+
+        let index = fs::read_to_string(file).map_err(|error| error.to_string()).and_then(|contents| {
+            match format {
+                Format::Csv => Index::from_csv(&contents),
+                Format::Tsv => Index::from_tsv(&contents),
+                Format::Opml => Index::from_opml(&contents),
+            }
+            .map_err(|error| error.to_string())
+        });
+
+        match index {
+            Ok(index) => println!("{index}"),
+            Err(message) => {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+
+        return;
+    }
+
+    if let Some(Commands::MergeDriver { base, ours, theirs }) = &cli.command {
+        process::exit(merge_driver(base, ours, theirs));
+    }
+
+    if let Some(Commands::Lsp {}) = &cli.command {
+        if let Err(error) = jd::lsp::run(io::stdin().lock(), io::stdout().lock()) {
+            eprintln!("{error}");
+        }
+
+        return;
+    }
+
+    let root = Config::load().and_then(|config| config::find_root(cli.dir.as_deref(), cli.system.as_deref(), config.as_ref(), |key| env::var(key).ok()));
+
+    if let Some(Commands::Sync { from_fs, from_index, interactive }) = &cli.command {
+        match &root {
+            Ok(_) if cli.no_index_file => println!("There is no index file to sync with."),
+            Ok(root) => sync(&root.to_string_lossy(), *from_fs, *from_index, *interactive),
+            Err(error) => println!("Invalid system: {error}"),
+        }
 
         return;
+    }
+
+    if let Some(Commands::Clean { fix_empty, fix_stray }) = &cli.command {
+        let mut options = CleanOptions::new().with_trash_empty(*fix_empty).with_index_file(!cli.no_index_file);
+
+        if let Some(inbox) = fix_stray {
+            options = options.with_inbox(inbox);
+        }
+
+        match root.and_then(|root| System::clean(&root.to_string_lossy(), &options)) {
+            Ok(problems) if problems.is_empty() => println!("No problems found."),
+            Ok(problems) => {
+                for (problem, fixed) in problems {
+                    println!("{}{problem}", if fixed { "Fixed: " } else { "" });
+                }
+            }
+            Err(error) => println!("{error}"),
+        }
+
+        return;
+    }
+
+    let options = SystemOptions::new().with_index_file(!cli.no_index_file);
+
+    let mut system = match root.and_then(|root| System::with_options(&root.to_string_lossy(), &options)) {
+        Ok(system) => system,
+        Err(error) => {
+            println!("Invalid system: {error}");
+
+            return;
+        }
     };
 
     match &cli.command {
-        Some(Commands::Add { name }) => {
-            let maybe_area_category_or_id = name.join(" ");
+        Some(Commands::Add { next, name }) => {
+            if *next {
+                println!("{}", add_next(&mut system, name));
+
+                return;
+            }
 
-            if let Ok(id) = Id::new(&maybe_area_category_or_id) {
-                match system.add_id(&id) {
+            match name.join(" ").parse::<Entry>() {
+                Ok(entry) if entry.is_reference() => println!("`{entry}` needs a title to be added."),
+
+                Ok(Entry::Id(id)) => match system.add_id(&id) {
                     Ok(ids) => {
                         dbg!(ids);
                     }
@@ -29,13 +115,9 @@ fn main() {
                     Err(message) => {
                         println!("{}", message)
                     }
-                }
+                },
 
-                return;
-            }
-
-            if let Ok(category) = Category::new(&maybe_area_category_or_id) {
-                match system.add_category(&category) {
+                Ok(Entry::Category(category)) => match system.add_category(&category) {
                     Ok(categories) => {
                         dbg!(categories);
                     }
@@ -43,13 +125,9 @@ fn main() {
                     Err(message) => {
                         println!("{}", message)
                     }
-                }
-
-                return;
-            }
+                },
 
-            if let Ok(area) = Area::new(&maybe_area_category_or_id) {
-                match system.add_area(&area) {
+                Ok(Entry::Area(area)) => match system.add_area(&area) {
                     Ok(areas) => {
                         dbg!(areas);
                     }
@@ -57,19 +135,30 @@ fn main() {
                     Err(message) => {
                         println!("{}", message)
                     }
-                }
+                },
 
-                return;
+                Err(error) => println!("{error}"),
             }
-
-            println!("Given string doesn't look like an area, category, or id.")
         }
 
         Some(Commands::Rm { name }) => {
-            let maybe_area_category_or_id = name.join(" ");
+            let entry = match name.join(" ").parse::<Entry>() {
+                Ok(entry) => entry,
+                Err(error) => {
+                    println!("{error}");
+
+                    return;
+                }
+            };
+
+            let Some(entry) = system.get_index().get_entry(&entry) else {
+                println!("{}", johnnydecimal::Error::NotFound(entry.get_number()));
+
+                return;
+            };
 
-            if let Ok(id) = Id::new(&maybe_area_category_or_id) {
-                match system.remove_id(&id) {
+            match entry {
+                Entry::Id(id) => match system.remove_id(&id) {
                     Ok(ids) => {
                         dbg!(ids);
                     }
@@ -77,13 +166,9 @@ fn main() {
                     Err(message) => {
                         println!("{}", message)
                     }
-                }
+                },
 
-                return;
-            }
-
-            if let Ok(category) = Category::new(&maybe_area_category_or_id) {
-                match system.remove_category(&category) {
+                Entry::Category(category) => match system.remove_category(&category) {
                     Ok(categories) => {
                         dbg!(categories);
                     }
@@ -91,13 +176,9 @@ fn main() {
                     Err(message) => {
                         println!("{}", message)
                     }
-                }
-
-                return;
-            }
+                },
 
-            if let Ok(area) = Area::new(&maybe_area_category_or_id) {
-                match system.remove_area(&area) {
+                Entry::Area(area) => match system.remove_area(&area) {
                     Ok(areas) => {
                         dbg!(areas);
                     }
@@ -105,20 +186,180 @@ fn main() {
                     Err(message) => {
                         println!("{}", message)
                     }
-                }
-
-                return;
+                },
             }
+        }
+
+        Some(Commands::Mv { id, category }) => {
+            let index = system.get_index();
+            let id = id.parse::<Entry>().map(|entry| index.get_entry(&entry));
+            let category = category.parse::<Entry>().map(|entry| index.get_entry(&entry));
+
+            match (id, category) {
+                (Ok(Some(Entry::Id(id))), Ok(Some(Entry::Category(category)))) => match system.move_id(&id, &category) {
+                    Ok((from, to)) => println!("Moved {from} to {to}"),
+                    Err(message) => println!("{message}"),
+                },
 
-            println!("Given string doesn't look like an area, category, or id.")
+                (Err(error), _) | (_, Err(error)) => println!("{error}"),
+                _ => println!("Give an id and a category that exist in index, like `jd mv 11.03 22`."),
+            }
         }
 
+        Some(Commands::Rename { number, title }) => match system.rename(number, &title.join(" ")) {
+            Ok((from, to)) => println!("Renamed {from} to {to}"),
+            Err(message) => println!("{message}"),
+        },
+
+        Some(Commands::Insert { name }) => match name.join(" ").parse::<Entry>() {
+            Ok(entry) if entry.is_reference() => println!("`{entry}` needs a title to be inserted."),
+            Ok(Entry::Id(id)) => print_insert(system.insert_id_at(&id), &id),
+            Ok(Entry::Category(category)) => print_insert(system.insert_category_at(&category), &category),
+            Ok(Entry::Area(_)) => println!("Only categories and ids can be inserted."),
+            Err(error) => println!("{error}"),
+        },
+
         Some(Commands::Index {}) => {
             println!("{}", system.get_index());
         }
 
-        Some(Commands::Clean {}) => {}
+        Some(Commands::Export { format }) => match format {
+            Format::Csv => print!("{}", system.get_index().to_csv()),
+            Format::Tsv => print!("{}", system.get_index().to_tsv()),
+            Format::Opml => print!("{}", system.get_index().to_opml()),
+        },
+
+        Some(Commands::Import { .. }) => {}
+
+        Some(Commands::Sync { .. }) => {}
+        Some(Commands::Clean { .. }) => {}
         Some(Commands::Serve {}) => {}
+        Some(Commands::Lsp {}) => {}
+        Some(Commands::MergeDriver { .. }) => {}
         None => {}
     }
 }
+
+/// Merges the index files of a git merge, returning the exit code for git.
+fn merge_driver(base: &Path, ours: &Path, theirs: &Path) -> i32 {
+    let read = |path: &Path| -> Result<Index, String> {
+        let string = fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))?;
+
+        Index::new(&string).map_err(|error| format!("{}: {error}", path.display()))
+    };
+
+    let (base, theirs_index, ours_index) = match (read(base), read(theirs), read(ours)) {
+        (Ok(base), Ok(theirs), Ok(ours)) => (base, theirs, ours),
+        (Err(message), _, _) | (_, Err(message), _) | (_, _, Err(message)) => {
+            eprintln!("{message}");
+
+            return 2;
+        }
+    };
+
+    let merge = Index::merge(&base, &ours_index, &theirs_index);
+
+    for conflict in merge.get_conflicts() {
+        eprintln!("Conflict: {conflict}");
+    }
+
+    if let Err(error) = fs::write(ours, format!("{}\n", merge.get_index())) {
+        eprintln!("{}: {error}", ours.display());
+
+        return 2;
+    }
+
+    i32::from(merge.has_conflicts())
+}
+
+/// Prints the differences between the index file and the directories of a system, resolving
+/// them from one side or one at a time first if asked to.
+fn sync(root: &str, from_fs: bool, from_index: bool, interactive: bool) {
+    let result = if interactive {
+        let mut lines = io::stdin().lines();
+
+        System::sync(root, |change| {
+            println!("{change}");
+            print!("Keep the [f]ilesystem, the [i]ndex file, or [s]kip? ");
+            let _ = io::stdout().flush();
+
+            match lines.next()?.ok()?.trim() {
+                "f" | "fs" => Some(Source::Filesystem),
+                "i" | "index" => Some(Source::IndexFile),
+                _ => None,
+            }
+        })
+    } else if from_fs {
+        System::sync(root, |_| Some(Source::Filesystem))
+    } else if from_index {
+        System::sync(root, |_| Some(Source::IndexFile))
+    } else {
+        System::check(root)
+    };
+
+    match result {
+        Ok(diff) if diff.is_empty() => println!("The index file and the directories are in sync."),
+        Ok(diff) => println!("The index file and the directories are different:\n{diff}"),
+        Err(message) => println!("{message}"),
+    }
+}
+
+/// Prints the entries shifted by an insert and the inserted entry, or why it failed.
+fn print_insert<T: Display>(result: Result<Vec<(T, T)>, jd::Error>, entry: &T) {
+    match result {
+        Ok(shifted) => {
+            for (from, to) in shifted {
+                println!("Shifted {from} to {to}");
+            }
+
+            println!("Inserted {entry}");
+        }
+
+        Err(message) => println!("{message}"),
+    }
+}
+
+/// Adds an entry with the next free number of a given category or area, like `jd add --next 11 Title`.
+///
+/// Returns what happened, or why nothing was added, like a category that doesn't exist.
+fn add_next(system: &mut System, name: &[String]) -> String {
+    let index = system.get_index();
+
+    let Some((number, title)) = name.split_first() else {
+        return "A category or area number is needed to add the next free id or category.".to_string();
+    };
+
+    let title = title.join(" ");
+
+    if let Ok(number) = number.parse::<CategoryNumber>() {
+        let Some(category) = index.get_category(number) else {
+            return johnnydecimal::Error::NotFound(number.to_string()).to_string();
+        };
+
+        let Some(id) = index.next_free_id(category) else {
+            return format!("Category {category} is full.");
+        };
+
+        let result = Id::new(&format!("{id} {title}")).map_err(jd::Error::from).and_then(|id| system.add_id(&id).map(|_| id));
+
+        return result.map_or_else(|error| error.to_string(), |id| format!("Added {id}"));
+    }
+
+    if let Ok(number) = number.parse::<AreaNumber>() {
+        let Some(area) = index.get_area(number) else {
+            return johnnydecimal::Error::NotFound(number.to_string()).to_string();
+        };
+
+        let Some(category) = index.next_free_category(area) else {
+            return format!("Area {area} is full.");
+        };
+
+        let result = Category::new(&format!("{category} {title}"))
+            .map_err(jd::Error::from)
+            .and_then(|category| system.add_category(&category).map(|_| category));
+
+        return result.map_or_else(|error| error.to_string(), |category| format!("Added {category}"));
+    }
+
+    format!("`{number}` is not a category or area number.")
+}
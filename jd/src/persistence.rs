@@ -0,0 +1,107 @@
+//! Writing `00.00 Index.txt` without losing the formatting chosen by the user.
+
+use crate::Error;
+use johnnydecimal::{Change, Document, Entry, Index};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts temporary files, so concurrent writes from one process never share a name.
+static TEMPORARY: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the contents of an index file changed to match a given `Index`.
+///
+/// Only the lines of changed entries are edited, so blank lines, indentation, and comments are
+/// kept. If the edited file wouldn't read back as the same `Index`, such as when a comment above
+/// a removed entry would move to the next one, the whole `Index` is written instead.
+pub(crate) fn update(str: &str, index: &Index) -> String {
+    let expected = index.to_string();
+
+    match edit(str, index) {
+        Some(document) if document.get_index().is_ok_and(|edited| edited.to_string() == expected) => document.to_string(),
+        _ => format!("{expected}\n"),
+    }
+}
+
+/// Writes `contents` to a file atomically by writing a temporary file next to it, syncing it to
+/// disk, and renaming it over the file.
+///
+/// The temporary file is named after the process and a counter, so concurrent writes never
+/// write to the same temporary file.
+///
+/// Readers see either the old or the new file, never a partial one. On Unix, the directory is
+/// synced too, so the rename itself survives a crash.
+pub(crate) fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    let Some(name) = path.file_name() else {
+        return Err(Error::System("Couldn't write the index file."));
+    };
+
+    let count = TEMPORARY.fetch_add(1, Ordering::Relaxed);
+    let temporary = path.with_file_name(format!(".{}.{}.{count}.tmp", name.to_string_lossy(), process::id()));
+
+    let Ok(mut file) = File::options().write(true).create_new(true).open(&temporary) else {
+        return Err(Error::System("Couldn't write the index file."));
+    };
+
+    let written = file.write_all(contents.as_bytes()).and_then(|()| file.sync_all());
+
+    drop(file);
+
+    if written.and_then(|()| fs::rename(&temporary, path)).is_err() {
+        let _ = fs::remove_file(&temporary);
+
+        return Err(Error::System("Couldn't write the index file."));
+    }
+
+    #[cfg(unix)]
+    {
+        let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+
+        if File::open(parent).and_then(|dir| dir.sync_all()).is_err() {
+            return Err(Error::System("Couldn't sync the directory of the index file."));
+        }
+    }
+
+    Ok(())
+}
+
+/// Edits a `Document` with the changes from the `Index` it contains to a given `Index`.
+///
+/// Removals come first, from the last entry up, so parents are removed after their children.
+/// Additions come last, in order, so parents are added before their children.
+fn edit(str: &str, index: &Index) -> Option<Document> {
+    let mut document = Document::new(str).ok()?;
+    let diff = document.get_index().ok()?.diff(index);
+    let mut added = vec![];
+
+    for change in diff.get_changes().iter().rev() {
+        match change {
+            Change::Removed(entry) => {
+                document.remove(&entry.get_number()).ok()?;
+            }
+            Change::Moved { from, to } => {
+                document.remove(&from.get_number()).ok()?;
+                added.push(to);
+            }
+            Change::Renamed { from, to } => {
+                document.rename(&from.get_number(), to.get_name()).ok()?;
+            }
+            Change::Added(entry) => added.push(entry),
+        }
+    }
+
+    // Numbers are zero-padded, so sorting them as text puts parents before their children.
+    added.sort_by_key(|entry| entry.get_number());
+
+    for entry in added {
+        match entry {
+            Entry::Area(area) => document.add_area(area).ok()?,
+            Entry::Category(category) => document.add_category(category).ok()?,
+            Entry::Id(id) => document.add_id(id).ok()?,
+        };
+    }
+
+    Some(document)
+}
@@ -0,0 +1,276 @@
+//! Resolving differences between `00.00 Index.txt` and the directories of a `System`.
+
+use crate::{derive_path, get_index_from_fs, persistence, strip_dir, Error, System, INDEX_FILE};
+use johnnydecimal::{Change, Diff, Entry, Index};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Which side is kept when the index file and the directories of a `System` disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Change the index file to match the directories.
+    Filesystem,
+    /// Change the directories to match the index file.
+    IndexFile,
+}
+
+impl System {
+    /// Returns the changes from `00.00 Index.txt` in `root` to the directories in `root`.
+    ///
+    /// This is the same `Diff` that `System::new` fails with. It's empty if there is no index file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use jd::System;
+    ///
+    /// let diff = System::check("../tests/sync").expect("should read index file");
+    ///
+    /// assert_eq!(diff.to_string(), "@@ removed 10-19 @@\n-10-19 Test");
+    /// ```
+    pub fn check(root: &str) -> Result<Diff, Error> {
+        let Ok(str) = fs::read_to_string(Path::new(root).join(INDEX_FILE)) else {
+            return Ok(Diff::default());
+        };
+
+        Ok(Index::new(&str)?.diff(&get_index_from_fs(root)?))
+    }
+
+    /// Resolves the changes from `00.00 Index.txt` in `root` to the directories in `root`, keeping
+    /// the `Source` returned by `decide` for each one, or neither if it returns `None`.
+    ///
+    /// Keeping the `Source::Filesystem` edits the index file, keeping its formatting. Keeping the
+    /// `Source::IndexFile` creates missing directories and renames directories with other titles
+    /// or numbers. Directories missing from the index file are never removed, so they're left in
+    /// the changes. The index file is checked before any directory changes. If successful, the
+    /// changes left are returned as `Ok`.
+    pub fn sync(root: &str, mut decide: impl FnMut(&Change) -> Option<Source>) -> Result<Diff, Error> {
+        let path = Path::new(root).join(INDEX_FILE);
+
+        let Ok(str) = fs::read_to_string(&path) else {
+            return Ok(Diff::default());
+        };
+
+        let mut index = Index::new(&str)?;
+        let diff = index.diff(&get_index_from_fs(root)?);
+        let mut from_fs = vec![];
+        let mut from_index = vec![];
+
+        for change in diff.get_changes() {
+            match decide(change) {
+                Some(Source::Filesystem) => from_fs.push(change),
+                Some(Source::IndexFile) => from_index.push(change),
+                None => {}
+            }
+        }
+
+        apply(&mut index, &from_fs)?;
+        run(root, &plan(root, &get_index_from_fs(root)?, &from_index)?)?;
+
+        if !from_fs.is_empty() {
+            persistence::write(&path, &persistence::update(&str, &index))?;
+        }
+
+        Ok(index.diff(&get_index_from_fs(root)?))
+    }
+}
+
+/// Applies changes to an `Index` like `persistence::update` does to a document.
+fn apply(index: &mut Index, changes: &[&Change]) -> Result<(), Error> {
+    let mut added = vec![];
+
+    for change in changes.iter().rev() {
+        match change {
+            Change::Removed(entry) => remove(index, entry)?,
+            Change::Moved { from, to } => {
+                remove(index, from)?;
+                added.push(to);
+            }
+            Change::Renamed { from, to } => {
+                index.rename(&from.get_number(), to.get_name())?;
+            }
+            Change::Added(entry) => added.push(entry),
+        }
+    }
+
+    added.sort_by_key(|entry| entry.get_number());
+
+    for entry in added {
+        match entry {
+            Entry::Area(area) => index.add_area(area).map(|_| ())?,
+            Entry::Category(category) => index.add_category(category).map(|_| ())?,
+            Entry::Id(id) => index.add_id(id).map(|_| ())?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes an area, category, or id from an `Index`.
+pub(crate) fn remove(index: &mut Index, entry: &Entry) -> Result<(), Error> {
+    match entry {
+        Entry::Area(area) => index.remove_area(area).map(|_| ())?,
+        Entry::Category(category) => index.remove_category(category).map(|_| ())?,
+        Entry::Id(id) => index.remove_id(id).map(|_| ())?,
+    }
+
+    Ok(())
+}
+
+/// A change to the directories of a `System`, with paths from its root like `/10-19 Area`.
+enum Step {
+    Rename(String, String),
+    Create(String),
+}
+
+/// Returns the steps that undo changes from the index file to the directories in `root`.
+///
+/// Changes are undone in the order of their entries, so parents are created or renamed first.
+/// Each path is where a directory will be after the steps before it, so ids are found even after
+/// their category is renumbered. Every step is checked against the directories as they'll be,
+/// so an `Err` is returned before anything changes.
+fn plan(root: &str, index: &Index, changes: &[&Change]) -> Result<Vec<Step>, Error> {
+    let mut steps = vec![];
+    let mut placed: HashMap<String, (String, usize)> = HashMap::new();
+
+    for change in changes {
+        let (from, to) = match change {
+            Change::Added(_) => continue,
+            Change::Removed(entry) => (entry, None),
+            Change::Renamed { from, to } | Change::Moved { from, to } => (from, Some(to)),
+        };
+
+        let parent = match parent_number(from) {
+            None => String::new(),
+            Some(number) => match placed.get(&number) {
+                Some((path, i)) => current(path, &steps[*i..]),
+                None => match number.parse().ok().and_then(|entry| index.get_entry(&entry)) {
+                    Some(entry) => current(&derive_path(index, &entry)?, &steps),
+                    None => return Err(Error::System("A directory in the index file has no parent directory.")),
+                },
+            },
+        };
+
+        let path = format!("{parent}/{from}");
+
+        if !exists_after(root, &parent, &steps) {
+            return Err(Error::System("A directory in the index file has no parent directory."));
+        }
+
+        if exists_after(root, &path, &steps) {
+            return Err(Error::System("A directory to sync to already exists."));
+        }
+
+        match to {
+            None => steps.push(Step::Create(path.clone())),
+            Some(to) => {
+                let source = current(&derive_path(index, to)?, &steps);
+
+                if !exists_after(root, &source, &steps) {
+                    return Err(Error::System("A directory to rename to match the index file couldn't be found."));
+                }
+
+                steps.push(Step::Rename(source, path.clone()));
+            }
+        }
+
+        placed.insert(from.get_number(), (path, steps.len()));
+    }
+
+    Ok(steps)
+}
+
+/// Changes the directories in `root` with steps from `plan`, undoing the steps already done if
+/// one fails.
+fn run(root: &str, steps: &[Step]) -> Result<(), Error> {
+    for (i, step) in steps.iter().enumerate() {
+        let done = match step {
+            Step::Rename(from, to) => fs::rename(format!("{root}{from}"), format!("{root}{to}")),
+            Step::Create(path) => fs::create_dir(format!("{root}{path}")),
+        };
+
+        if done.is_err() {
+            for step in steps[..i].iter().rev() {
+                let _ = match step {
+                    Step::Rename(from, to) => fs::rename(format!("{root}{to}"), format!("{root}{from}")),
+                    Step::Create(path) => fs::remove_dir(format!("{root}{path}")),
+                };
+            }
+
+            return Err(Error::System("A directory couldn't be changed to match the index file."));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns where a directory will be after some steps.
+fn current(path: &str, steps: &[Step]) -> String {
+    let mut path = path.to_string();
+
+    for step in steps {
+        if let Step::Rename(from, to) = step {
+            if let Some(rest) = strip_dir(&path, from) {
+                path = format!("{to}{rest}");
+            }
+        }
+    }
+
+    path
+}
+
+/// Returns true if a directory will exist after some steps, which haven't happened yet.
+fn exists_after(root: &str, path: &str, steps: &[Step]) -> bool {
+    let mut path = path.to_string();
+
+    for step in steps.iter().rev() {
+        match step {
+            Step::Rename(from, to) => {
+                if let Some(rest) = strip_dir(&path, to) {
+                    path = format!("{from}{rest}");
+                } else if strip_dir(&path, from).is_some() {
+                    return false;
+                }
+            }
+            Step::Create(created) => match strip_dir(&path, created) {
+                Some("") => return true,
+                Some(_) => return false,
+                None => {}
+            },
+        }
+    }
+
+    Path::new(&format!("{root}{path}")).exists()
+}
+
+/// Returns the number of the parent of an area, category, or id, if it has one.
+fn parent_number(entry: &Entry) -> Option<String> {
+    match entry {
+        Entry::Area(_) => None,
+        Entry::Category(category) => Some(category.get_area().to_string()),
+        Entry::Id(id) => Some(id.get_category().to_string()),
+    }
+}
+
+/// Returns the directory of an area, category, or id in `root` with the number of a given entry.
+pub(crate) fn find_dir(root: &Path, entry: &Entry) -> Option<PathBuf> {
+    let number = entry.get_number();
+
+    fs::read_dir(parent_dir(root, entry)?).ok()?.flatten().map(|dir| dir.path()).find(|path| {
+        let name = path.file_name().and_then(|name| name.to_str());
+
+        path.is_dir()
+            && name
+                .and_then(|name| name.parse::<Entry>().ok())
+                .is_some_and(|found| !found.is_reference() && found.get_number() == number)
+    })
+}
+
+/// Returns the directory that an area, category, or id belongs in, if it exists.
+fn parent_dir(root: &Path, entry: &Entry) -> Option<PathBuf> {
+    match parent_number(entry) {
+        Some(number) => find_dir(root, &number.parse().ok()?),
+        None => Some(root.to_path_buf()),
+    }
+}
@@ -0,0 +1,130 @@
+//! Tests for `System::clean`.
+
+use jd::{CleanOptions, Problem, System};
+use std::fs;
+use std::path::Path;
+
+/// Creates a new system in a temporary directory with some directories and files.
+fn temp_system(name: &str, dirs: &[&str], files: &[&str]) -> String {
+    let root = std::env::temp_dir().join(format!("jd-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    for dir in dirs {
+        fs::create_dir_all(root.join(dir)).expect("should create directory");
+    }
+
+    for file in files {
+        fs::write(root.join(file), "").expect("should write file");
+    }
+
+    root.to_string_lossy().to_string()
+}
+
+#[test]
+fn test_clean() {
+    let dirs = [
+        "10-19 Area/11 Category/11.01 Empty",
+        "10-19 Area/11 Category/11.02 Full",
+        "10-19 Area/1x Typo",
+        "10-19 Area/21 Elsewhere/21.01 Id",
+        "Documents",
+    ];
+    let files = [
+        "10-19 Area/11 Category/11.02 Full/file.txt",
+        "10-19 Area/11 Category/stray.txt",
+        "10-19 Area/11 Category/.hidden",
+        "10-19 Area/21 Elsewhere/21.01 Id/file.txt",
+        "notes.txt",
+    ];
+    let root = temp_system("clean", &dirs, &files);
+    let problems = System::clean(&root, &CleanOptions::new()).expect("should be able to clean");
+
+    assert_eq!(problems.len(), 4, "should find every problem and nothing else");
+    assert!(problems.iter().all(|(_, fixed)| !fixed), "should not fix anything by default");
+    assert_eq!(problems[0].0, Problem::Empty("/10-19 Area/11 Category/11.01 Empty".to_string()), "should find empty ids");
+    assert_eq!(problems[1].0, Problem::Stray("/10-19 Area/11 Category/stray.txt".to_string()), "should find stray files");
+    assert!(matches!(&problems[2].0, Problem::Misnamed { path, .. } if path == "/10-19 Area/1x Typo"), "should find invalid names");
+    assert!(
+        matches!(&problems[3].0, Problem::Misplaced { path, .. } if path == "/10-19 Area/21 Elsewhere"),
+        "should find misplaced categories"
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_clean_stray_files() {
+    let dirs = ["00-09 System/01 Inbox/01.01 Inbox", "10-19 Area/11 Category/11.01 Id"];
+    let files = ["10-19 Area/stray.txt", "10-19 Area/11 Category/11.01 Id/file.txt"];
+    let root = temp_system("clean-stray", &dirs, &files);
+
+    assert!(System::clean(&root, &CleanOptions::new().with_inbox("11")).is_err(), "should fail if the inbox isn't an id");
+    assert!(System::clean(&root, &CleanOptions::new().with_inbox("01.02")).is_err(), "should fail if the inbox doesn't exist");
+
+    let options = CleanOptions::new().with_trash_empty(true).with_inbox("01.01");
+
+    assert_eq!(
+        System::clean(&root, &options),
+        Ok(vec![(Problem::Stray("/10-19 Area/stray.txt".to_string()), true)]),
+        "should move stray files without trashing the empty inbox"
+    );
+    assert!(
+        Path::new(&format!("{root}/00-09 System/01 Inbox/01.01 Inbox/stray.txt")).is_file(),
+        "should move stray files to the inbox"
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_clean_indexed_empty_directories() {
+    let dirs = ["10-19 Area/11 Category/11.01 Full", "10-19 Area/11 Category/11.02 Empty"];
+    let files = ["10-19 Area/11 Category/11.01 Full/file.txt"];
+    let root = temp_system("clean-indexed", &dirs, &files);
+
+    fs::write(format!("{root}/00.00 Index.txt"), "// My system\n10-19 Area\n11 Category\n11.01 Full\n11.02 Empty\n").expect("should write index file");
+
+    let options = CleanOptions::new().with_trash_empty(true);
+
+    assert_eq!(
+        System::clean(&root, &options),
+        Ok(vec![(Problem::Empty("/10-19 Area/11 Category/11.02 Empty".to_string()), true)]),
+        "should trash empty ids in the index"
+    );
+    assert_eq!(
+        fs::read_to_string(format!("{root}/00.00 Index.txt")).ok(),
+        Some("// My system\n10-19 Area\n11 Category\n11.01 Full\n".to_string()),
+        "should remove trashed ids from the index file"
+    );
+    assert!(System::new(&root).is_ok(), "should stay in sync with the index file");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_clean_only_removes_matching_entries() {
+    let dirs = ["10-19 Area/21 Elsewhere", "20-29 Other/21 Elsewhere/21.01 Id", "30-39 Parent"];
+    let files = ["20-29 Other/21 Elsewhere/21.01 Id/file.txt"];
+    let root = temp_system("clean-matching", &dirs, &files);
+    let index = "10-19 Area\n20-29 Other\n21 Elsewhere\n21.01 Id\n30-39 Parent\n31 Child\n";
+
+    fs::write(format!("{root}/00.00 Index.txt"), index).expect("should write index file");
+
+    let problems = System::clean(&root, &CleanOptions::new().with_trash_empty(true)).expect("should be able to clean");
+
+    assert!(
+        problems.contains(&(Problem::Empty("/10-19 Area/21 Elsewhere".to_string()), true)),
+        "should trash empty misplaced categories"
+    );
+    assert!(
+        problems.contains(&(Problem::Empty("/30-39 Parent".to_string()), false)),
+        "should not trash empty areas with categories in the index"
+    );
+    assert_eq!(
+        fs::read_to_string(format!("{root}/00.00 Index.txt")).ok().as_deref(),
+        Some(index),
+        "should not remove entries at other paths"
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
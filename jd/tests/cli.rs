@@ -0,0 +1,65 @@
+//! Tests for the `jd` command line interface.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Runs `jd` with a given root and arguments, returning what it printed.
+fn jd(root: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_jd"))
+        .arg("--dir")
+        .arg(root)
+        .args(args)
+        .env("XDG_CONFIG_HOME", root.join(".config"))
+        .output()
+        .expect("should run jd");
+
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_add_to_empty_root() {
+    let root = std::env::temp_dir().join(format!("jd-cli-add-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).expect("should create root");
+
+    jd(&root, &["add", "10-19", "Area"]);
+    jd(&root, &["add", "11", "Category"]);
+
+    assert!(root.join("10-19 Area/11 Category").is_dir(), "should add a new area and category to an empty root");
+    assert_eq!(
+        jd(&root, &["add", "--next", "11", "First", "id"]),
+        "Added 11.01 First id\n",
+        "should use the next free id of an existing category"
+    );
+    assert!(root.join("10-19 Area/11 Category/11.01 First id").is_dir(), "should create the allocated id");
+    assert_eq!(jd(&root, &["add", "--next", "12", "Title"]), "`12` doesn't exist\n", "should not guess when the category doesn't exist");
+    assert!(!root.join("10-19 Area/12 Title").exists(), "should not create a category instead");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_import_errors() {
+    let root = std::env::temp_dir().join(format!("jd-cli-import-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).expect("should create root");
+    fs::write(root.join("index.csv"), "kind,area,category,id,title\narea,10-19,,,../Invalid").expect("should write table");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_jd"))
+        .arg("--dir")
+        .arg(&root)
+        .arg("import")
+        .arg(root.join("index.csv"))
+        .env("XDG_CONFIG_HOME", root.join(".config"))
+        .output()
+        .expect("should run jd");
+
+    assert!(!output.status.success(), "should fail to import an invalid table");
+    assert!(output.stdout.is_empty(), "should not print errors as an index");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Row 2"), "should print where the problem is");
+
+    let _ = fs::remove_dir_all(root);
+}
@@ -0,0 +1,64 @@
+//! Tests for `Config` and choosing the root of a `System`.
+
+use jd::config::{config_path, find_root};
+use jd::{Config, Error};
+use std::path::{Path, PathBuf};
+
+const CONFIG: &str = r#"
+root = "~/Documents"
+
+[systems.work]
+root = "/srv/work"
+"#;
+
+/// Returns environment variables from a list of pairs.
+fn vars(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+    move |key| pairs.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string())
+}
+
+#[test]
+fn test_config() {
+    let config = Config::new(CONFIG).expect("config should be valid");
+
+    assert_eq!(config.get_root(), Some(Path::new("~/Documents")), "should read the default root");
+    assert_eq!(config.get_system("work"), Some(Path::new("/srv/work")), "should read named systems");
+    assert_eq!(config.get_system("home"), None, "should not have other systems");
+    assert_eq!(Config::new(""), Ok(Config::default()), "should allow empty config files");
+
+    assert!(matches!(Config::new("root = 1"), Err(Error::Config(_))), "should fail if root isn't a string");
+    assert!(matches!(Config::new("[systems.work]\ndir = \"/srv\""), Err(Error::Config(_))), "should fail if a system has no root");
+    assert!(matches!(Config::new("rot = \"/srv\""), Err(Error::Config(_))), "should fail for unknown keys");
+    assert!(matches!(Config::new("root = "), Err(Error::Config(_))), "should fail for invalid TOML");
+}
+
+#[test]
+fn test_config_path() {
+    assert_eq!(
+        config_path(vars(&[("XDG_CONFIG_HOME", "/xdg"), ("HOME", "/home/user")])),
+        Some(PathBuf::from("/xdg/jd/config.toml")),
+        "should prefer XDG_CONFIG_HOME"
+    );
+    assert_eq!(
+        config_path(vars(&[("HOME", "/home/user")])),
+        Some(PathBuf::from("/home/user/.config/jd/config.toml")),
+        "should fall back to ~/.config"
+    );
+    assert_eq!(config_path(vars(&[])), None, "should not have a path without a home");
+}
+
+#[test]
+fn test_find_root() {
+    let config = Config::new(CONFIG).expect("config should be valid");
+    let env = vars(&[("JD_ROOT", "/env"), ("HOME", "/home/user")]);
+    let home = vars(&[("HOME", "/home/user")]);
+    let root = |path: &str| Ok(PathBuf::from(path));
+
+    assert_eq!(find_root(Some(Path::new("/dir")), Some("work"), Some(&config), &env), root("/dir"), "should prefer --dir");
+    assert_eq!(find_root(None, Some("work"), Some(&config), &env), root("/srv/work"), "should use --system next");
+    assert_eq!(find_root(None, None, Some(&config), &env), root("/env"), "should use JD_ROOT next");
+    assert_eq!(find_root(None, None, Some(&config), &home), root("/home/user/Documents"), "should use the config root next");
+    assert_eq!(find_root(None, None, None, &home), root("/home/user"), "should use HOME last");
+
+    assert!(matches!(find_root(None, Some("home"), Some(&config), &env), Err(Error::Config(_))), "should fail for unknown systems");
+    assert!(find_root(None, None, None, vars(&[])).is_err(), "should fail without any root");
+}
@@ -0,0 +1,124 @@
+//! Tests for the Language Server.
+
+use jd::lsp::{run, Server};
+use serde_json::{json, Value};
+
+const URI: &str = "file:///tmp/00.00%20Index.txt";
+const SOURCE: &str = "10-19 Area\n   11 Category\n      11.01 Id\n      11.03 Third\n      \n   13.01 Orphan\n20-29 Second";
+
+fn open(server: &mut Server, text: &str) -> Vec<Value> {
+    server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": URI, "languageId": "jd", "version": 1, "text": text } },
+    }))
+}
+
+fn request(server: &mut Server, method: &str, line: usize, character: usize) -> Value {
+    let responses = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": { "textDocument": { "uri": URI }, "position": { "line": line, "character": character } },
+    }));
+
+    responses[0]["result"].clone()
+}
+
+#[test]
+fn test_diagnostics() {
+    let mut server = Server::new();
+    let notifications = open(&mut server, SOURCE);
+    let diagnostics = notifications[0]["params"]["diagnostics"].as_array().expect("should publish diagnostics");
+
+    assert_eq!(diagnostics.len(), 1, "should publish one diagnostic");
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 5, "should use 0-based lines");
+    assert_eq!(diagnostics[0]["severity"], 1, "orphans should be errors");
+
+    let notifications = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": { "textDocument": { "uri": URI, "version": 2 }, "contentChanges": [{ "text": "10-19 Area\n10-19 Again" }] },
+    }));
+
+    assert_eq!(notifications[0]["params"]["diagnostics"][0]["range"]["start"]["line"], 1, "should report duplicates after changes");
+
+    let notifications = open(&mut server, "10-19 Area\n   11Category");
+    let range = &notifications[0]["params"]["diagnostics"][0]["range"];
+
+    assert_eq!(range["start"], json!({ "line": 1, "character": 5 }), "should start parse errors at their column");
+    assert_eq!(range["end"], json!({ "line": 1, "character": 13 }), "should end parse errors at the end of the line");
+}
+
+#[test]
+fn test_completion() {
+    let mut server = Server::new();
+
+    open(&mut server, SOURCE);
+
+    let items = request(&mut server, "textDocument/completion", 4, 6);
+
+    assert_eq!(items[0]["label"], "11.02", "should complete the lowest free id of the nearest category");
+    assert_eq!(items[0]["textEdit"]["newText"], "11.02 ", "should insert the id");
+
+    let items = request(&mut server, "textDocument/completion", 6, 0);
+
+    assert_eq!(items, json!([]), "should not complete without a category");
+}
+
+#[test]
+fn test_hover_and_definition() {
+    let mut server = Server::new();
+
+    open(&mut server, SOURCE);
+
+    let hover = request(&mut server, "textDocument/hover", 2, 8);
+
+    assert_eq!(hover["contents"]["value"], "`10-19 Area` > `11 Category`", "should show parent area and category");
+
+    let definition = request(&mut server, "textDocument/definition", 3, 8);
+
+    assert_eq!(definition["uri"], URI, "should go to the same document");
+    assert_eq!(definition["range"]["start"]["line"], 1, "should go to the category line");
+    assert_eq!(request(&mut server, "textDocument/hover", 6, 0), Value::Null, "areas have no parents");
+}
+
+#[test]
+fn test_symbols() {
+    let mut server = Server::new();
+
+    open(&mut server, SOURCE);
+
+    let symbols = request(&mut server, "textDocument/documentSymbol", 0, 0);
+    let names: Vec<&str> = symbols
+        .as_array()
+        .expect("should return symbols")
+        .iter()
+        .map(|symbol| symbol["name"].as_str().unwrap_or_default())
+        .collect();
+
+    assert_eq!(names, vec!["10-19 Area", "20-29 Second", "13.01 Orphan"], "should list areas and orphans at the top");
+    assert_eq!(symbols[0]["children"][0]["name"], "11 Category", "should nest categories in areas");
+    assert_eq!(symbols[0]["children"][0]["children"].as_array().map(Vec::len), Some(2), "should nest ids in categories");
+}
+
+#[test]
+fn test_run() {
+    let messages = [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "unknown" }),
+        json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+        json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" }),
+    ];
+
+    let input: String = messages.iter().map(|message| format!("Content-Length: {}\r\n\r\n{message}", message.to_string().len())).collect();
+    let mut output = vec![];
+
+    run(input.as_bytes(), &mut output).expect("should run until exit");
+
+    let output = String::from_utf8(output).expect("output should be UTF-8");
+
+    assert_eq!(output.matches("Content-Length").count(), 3, "should respond to requests before exit");
+    assert!(output.contains("\"code\":-32601"), "should fail on unknown methods");
+}
@@ -1,31 +1,58 @@
-use jd::System;
-use johnnydecimal::{Area, Category, Id, Index};
+//! Tests for `System`.
+
+use jd::{Source, System, SystemOptions};
+use johnnydecimal::{Area, Category, Diff, Id, Index};
+use std::fs;
+use std::path::Path;
+
+/// Creates a new system in a temporary directory with some directories and an index file.
+fn temp_system(name: &str, dirs: &[&str], index: Option<&str>) -> String {
+    let root = std::env::temp_dir().join(format!("jd-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+
+    for dir in dirs {
+        fs::create_dir_all(root.join(dir)).expect("should create directory");
+    }
+
+    if let Some(index) = index {
+        fs::write(root.join("00.00 Index.txt"), index).expect("should write index file");
+    }
+
+    root.to_string_lossy().to_string()
+}
 
 #[test]
 fn test_adding_and_removing_things() {
-    let mut system = System::new("../tests/empty").expect("Test system should be valid");
+    let options = SystemOptions::new().with_index_file(false);
+    let mut system = System::with_options("../tests/empty", &options).expect("Test system should be valid");
 
     let area = Area::new("40-49 Area 4").expect("`40-49 Area 4` should be valid");
     let category = Category::new("43 New Category").expect("`43 New Category` should be valid");
     let id = Id::new("43.05 New Id").expect("`43.05 New Id` should be valid");
 
-    assert_eq!(system.add_area(&area), Ok(&vec![area.clone()]), "should be able to add a new area `40-49 Area 4`");
+    assert_eq!(system.add_area(&area), Ok(vec![&area]), "should be able to add a new area `40-49 Area 4`");
     assert!(system.add_area(&area).is_err(), "should fail when adding an existing area");
 
-    assert_eq!(system.add_category(&category), Ok(&vec![category.clone()]), "should be able to add a new category `43 New Category`");
+    assert_eq!(system.add_category(&category), Ok(vec![&category]), "should be able to add a new category `43 New Category`");
     assert!(system.add_category(&category).is_err(), "should fail when adding an existing category");
 
-    assert_eq!(system.add_id(&id), Ok(&vec![id.clone()]), "should be able to add a new id `43.05 New Id`");
+    assert_eq!(system.add_id(&id), Ok(vec![&id]), "should be able to add a new id `43.05 New Id`");
     assert!(system.add_id(&id).is_err(), "should fail when adding an existing id");
 
     assert!(system.remove_id(&id).is_ok(), "should be able to remove the added id `43.05 New Id`");
     assert!(system.remove_category(&category).is_ok(), "should be able to remove the added category `43 New Category`");
     assert!(system.remove_area(&area).is_ok(), "should be able to remove the added area `40-49 Area 4`");
+
+    assert!(system.remove_id(&id).is_err(), "should fail when removing an id that isn't in the index");
+    assert!(system.remove_category(&category).is_err(), "should fail when removing a category that isn't in the index");
+    assert!(system.remove_area(&area).is_err(), "should fail when removing an area that isn't in the index");
 }
 
 #[test]
 fn file_index_different_than_filesystem() {
-    assert!(System::new("../tests/sync").is_err(), "should fail if index different than filesystem (out of sync)");
+    let error = System::new("../tests/sync").expect_err("should fail if index different than filesystem (out of sync)");
+
+    assert!(error.to_string().ends_with("@@ removed 10-19 @@\n-10-19 Test"), "should say what is different");
 }
 
 #[test]
@@ -52,4 +79,224 @@ fn read_from_file() {
     let index = Index::with_vecs(&areas, &categories, &ids).expect("should be valid index");
 
     assert_eq!(*system.get_index(), index, "should create correct index from verified index file");
+    assert_eq!(
+        system.get_index().get_ids().next().expect("should have an id").get_metadata().get("location"),
+        Some("Desk drawer"),
+        "should read metadata from index file"
+    );
+}
+
+#[test]
+fn test_move_id() {
+    let index = "10-19 Area\n11 Category\n11.03 Moved\n- location: Desk\n12 Other\n12.01 First";
+    let dirs = ["10-19 Area/11 Category/11.03 Moved", "10-19 Area/12 Other/12.01 First"];
+    let root = temp_system("move", &dirs, Some(index));
+    let mut system = System::new(&root).expect("should be valid");
+
+    let id = Id::new("11.03 Moved").expect("should be valid id");
+    let category = Category::new("12 Other").expect("should be valid category");
+
+    assert_eq!(
+        system.move_id(&id, &category),
+        Ok(("/10-19 Area/11 Category/11.03 Moved".to_string(), "/10-19 Area/12 Other/12.02 Moved".to_string())),
+        "should return the old and new paths"
+    );
+
+    assert!(Path::new(&format!("{root}/10-19 Area/12 Other/12.02 Moved")).is_dir(), "should rename the directory");
+    assert!(!Path::new(&format!("{root}/10-19 Area/11 Category/11.03 Moved")).exists(), "should not keep the old directory");
+
+    let system = System::new(&root).expect("should update the index file");
+
+    assert_eq!(
+        system.get_index().get_id("12.02").map(|id| id.get_metadata().get("location")),
+        Some(Some("Desk")),
+        "should keep metadata"
+    );
+    assert!(system.get_index().get_id("11.03").is_none(), "should remove the old number");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_insert_category_at() {
+    let dirs = ["10-19 Area/11 First/11.01 Id", "10-19 Area/12 Second"];
+    let root = temp_system("insert", &dirs, None);
+    let mut system = System::new(&root).expect("should be valid");
+
+    let category = Category::new("11 New").expect("should be valid category");
+
+    assert_eq!(system.insert_category_at(&category).map(|shifted| shifted.len()), Ok(2), "should shift both categories");
+
+    for dir in ["11 New", "12 First/12.01 Id", "13 Second"] {
+        assert!(Path::new(&format!("{root}/10-19 Area/{dir}")).is_dir(), "should have `{dir}` after shifting");
+    }
+
+    let id = Id::new("12.01 Clobbered").expect("should be valid id");
+
+    fs::create_dir(format!("{root}/10-19 Area/12 First/12.02 Id")).expect("should create directory");
+
+    assert!(system.insert_id_at(&id).is_err(), "should fail instead of clobbering directories");
+    assert!(Path::new(&format!("{root}/10-19 Area/12 First/12.01 Id")).is_dir(), "should not change anything if it would clobber");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_insert_with_equal_titles() {
+    let dirs = ["10-19 Area/11 Notes/11.01 Notes", "10-19 Area/11 Notes/11.02 Notes", "10-19 Area/12 Notes/12.01 Notes"];
+    let root = temp_system("insert-equal", &dirs, None);
+    let mut system = System::new(&root).expect("should be valid");
+
+    let id = Id::new("11.01 New").expect("should be valid id");
+    let category = Category::new("11 New").expect("should be valid category");
+
+    assert!(system.insert_id_at(&id).is_ok(), "should shift ids with the same title");
+    assert!(system.insert_category_at(&category).is_ok(), "should shift categories with the same title and ids");
+
+    for dir in ["11 New", "12 Notes/12.01 New", "12 Notes/12.02 Notes", "12 Notes/12.03 Notes", "13 Notes/13.01 Notes"] {
+        assert!(Path::new(&format!("{root}/10-19 Area/{dir}")).is_dir(), "should have `{dir}` after shifting");
+    }
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_rename() {
+    let index = "10-19 Area\n11 Category\n11.01 Id\n- location: Desk\n11.02 Other";
+    let dirs = ["10-19 Area/11 Category/11.01 Id", "10-19 Area/11 Category/11.02 Other"];
+    let root = temp_system("rename", &dirs, Some(index));
+    let mut system = System::new(&root).expect("should be valid");
+
+    assert_eq!(
+        system.rename("11.01", "New title"),
+        Ok(("/10-19 Area/11 Category/11.01 Id".to_string(), "/10-19 Area/11 Category/11.01 New title".to_string())),
+        "should return the old and new paths"
+    );
+
+    assert!(system.rename("11", "Renamed").is_ok(), "should rename categories with children");
+    assert!(Path::new(&format!("{root}/10-19 Area/11 Renamed/11.01 New title")).is_dir(), "should rename the directories");
+    assert!(system.rename("11.03", "Missing").is_err(), "should fail for missing entries");
+
+    let system = System::new(&root).expect("should update the index file");
+
+    assert_eq!(
+        system.get_index().to_string(),
+        "10-19 Area\n11 Renamed\n11.01 New title\n- location: Desk\n11.02 Other",
+        "should keep the other entries"
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_persist_index_file() {
+    let index = "// My system\n10-19 Area\n\n    11 Category // Inline\n    - owner: Alice\n";
+    let root = temp_system("persist", &["10-19 Area/11 Category"], Some(index));
+    let mut system = System::new(&root).expect("should be valid");
+
+    system.add_id(&Id::new("11.01 Id").expect("should be valid id")).expect("should add id");
+    system.add_category(&Category::new("12 Other").expect("should be valid category")).expect("should add category");
+    system.remove_category(&Category::new("12 Other").expect("should be valid category")).expect("should remove category");
+
+    assert_eq!(
+        fs::read_to_string(format!("{root}/00.00 Index.txt")).expect("should have index file"),
+        "// My system\n10-19 Area\n\n    11 Category // Inline\n    - owner: Alice\n11.01 Id\n",
+        "should keep comments, metadata, and formatting"
+    );
+    assert!(!Path::new(&format!("{root}/.00.00 Index.txt.tmp")).exists(), "should not leave temporary files");
+    assert!(System::new(&root).is_ok(), "should stay in sync with the filesystem");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_without_index_file() {
+    let root = temp_system("no-index", &["10-19 Area"], None);
+    let mut system = System::new(&root).expect("should be valid");
+
+    system.add_category(&Category::new("11 Category").expect("should be valid category")).expect("should add category");
+
+    assert_eq!(
+        fs::read_to_string(format!("{root}/00.00 Index.txt")).expect("should create index file"),
+        "10-19 Area\n11 Category\n",
+        "should create the index file on the first change"
+    );
+
+    fs::write(format!("{root}/00.00 Index.txt"), "Not an index").expect("should write index file");
+
+    let options = SystemOptions::new().with_index_file(false);
+    let mut system = System::with_options(&root, &options).expect("should ignore the index file");
+
+    system.add_id(&Id::new("11.01 Id").expect("should be valid id")).expect("should add id");
+
+    assert_eq!(
+        fs::read_to_string(format!("{root}/00.00 Index.txt")).ok(),
+        Some("Not an index".to_string()),
+        "should never write the index file"
+    );
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_sync_from_fs() {
+    let index = "// My system\n10-19 Area\n   11 Category\n      11.01 Old\n      - location: Desk\n      11.02 Missing\n";
+    let dirs = ["10-19 Area/11 Category/11.01 New", "10-19 Area/11 Category/11.03 Extra"];
+    let root = temp_system("sync-fs", &dirs, Some(index));
+
+    assert_eq!(System::check(&root).map(|diff| diff.get_changes().len()), Ok(3), "should report every difference");
+    assert_eq!(System::sync(&root, |_| None).map(|diff| diff.get_changes().len()), Ok(3), "should keep skipped differences");
+    assert_eq!(System::sync(&root, |_| Some(Source::Filesystem)), Ok(Diff::default()), "should resolve every difference");
+
+    assert_eq!(
+        fs::read_to_string(format!("{root}/00.00 Index.txt")).expect("should have index file"),
+        "// My system\n10-19 Area\n   11 Category\n      11.01 New\n      - location: Desk\n      11.03 Extra\n",
+        "should edit the index file, keeping comments and metadata"
+    );
+    assert!(System::new(&root).is_ok(), "should be in sync afterwards");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_sync_from_index() {
+    let index = "10-19 Area\n11 Category\n11.01 Id\n11.02 Moved\n12 Missing\n12.01 Child";
+    let dirs = ["10-19 Old area/11 Category/11.01 Old", "10-19 Old area/11 Category/11.05 Moved", "10-19 Old area/13 Extra"];
+    let root = temp_system("sync-index", &dirs, Some(index));
+
+    assert_eq!(
+        System::sync(&root, |_| Some(Source::IndexFile)).map(|diff| diff.to_string()),
+        Ok("@@ added 13 @@\n+13 Extra".to_string()),
+        "should resolve every difference but leave directories missing from the index file"
+    );
+    assert!(Path::new(&format!("{root}/10-19 Area/13 Extra")).is_dir(), "should not remove directories missing from the index file");
+
+    for dir in ["11 Category/11.01 Id", "11 Category/11.02 Moved", "12 Missing/12.01 Child"] {
+        assert!(Path::new(&format!("{root}/10-19 Area/{dir}")).is_dir(), "should have `{dir}` like the index file");
+    }
+
+    assert!(!Path::new(&format!("{root}/10-19 Old area")).exists(), "should rename instead of copying");
+    assert_eq!(fs::read_to_string(format!("{root}/00.00 Index.txt")).ok(), Some(index.to_string()), "should not change the index file");
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_sync_renumbered_category() {
+    let index = "10-19 Area\n11 Category\n11.01 Id\n11.02 Other";
+    let dirs = ["10-19 Area/12 Category/12.01 Id", "10-19 Area/12 Category/12.02 Other"];
+    let root = temp_system("sync-renumbered", &dirs, Some(index));
+
+    assert_eq!(System::sync(&root, |_| Some(Source::IndexFile)), Ok(Diff::default()), "should renumber the category and its ids");
+    assert!(Path::new(&format!("{root}/10-19 Area/11 Category/11.02 Other")).is_dir(), "should move ids along with their category");
+    assert!(System::new(&root).is_ok(), "should be in sync afterwards");
+
+    fs::rename(format!("{root}/10-19 Area/11 Category"), format!("{root}/10-19 Area/12 Category")).expect("should rename directory");
+    fs::rename(format!("{root}/10-19 Area/12 Category/11.01 Id"), format!("{root}/10-19 Area/12 Category/12.01 Id")).expect("should rename directory");
+    fs::write(format!("{root}/10-19 Area/12 Category/11.01 Id"), "").expect("should write file");
+
+    assert!(System::sync(&root, |_| Some(Source::IndexFile)).is_err(), "should fail if a directory would be clobbered");
+    assert!(Path::new(&format!("{root}/10-19 Area/12 Category/12.01 Id")).is_dir(), "should not change anything if it would fail");
+
+    let _ = fs::remove_dir_all(root);
 }
@@ -0,0 +1,116 @@
+//! Allocation of the next free category of an area and id of a category.
+
+use crate::{Area, Category, CategoryNumber, IdNumber, Index};
+
+/// Which free number `Index::next_free_id_with` and `Index::next_free_category_with` choose.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// The lowest number that isn't used, filling gaps left by removed entries.
+    #[default]
+    LowestGap,
+
+    /// The number after the highest one that is used, so numbers are never reused.
+    AfterHighest,
+}
+
+/// Options for `Index::next_free_id_with` and `Index::next_free_category_with`.
+///
+/// By default, the lowest gap is chosen and the reserved numbers `x0` and `.00` are skipped,
+/// since Johnny.Decimal keeps them for managing the area or category itself.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::{AllocationOptions, Index, Policy};
+///
+/// let index = Index::new("10-19 Area\n11 Category\n11.01 First\n11.03 Third").expect("Invalid index");
+/// let category = index.get_category("11").expect("Category should exist");
+///
+/// assert_eq!(index.next_free_id(category).expect("Category is full"), "11.02");
+///
+/// let options = AllocationOptions::new().with_policy(Policy::AfterHighest);
+///
+/// assert_eq!(index.next_free_id_with(category, &options).expect("Category is full"), "11.04");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllocationOptions {
+    policy: Policy,
+    skip_reserved: bool,
+}
+
+impl Default for AllocationOptions {
+    fn default() -> Self {
+        Self {
+            policy: Policy::LowestGap,
+            skip_reserved: true,
+        }
+    }
+}
+
+impl AllocationOptions {
+    /// Creates the default `AllocationOptions`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Choose the lowest gap or the number after the highest.
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Skip the reserved category `x0` of each area and the reserved id `.00` of each category.
+    pub fn with_skip_reserved(mut self, skip_reserved: bool) -> Self {
+        self.skip_reserved = skip_reserved;
+        self
+    }
+}
+
+impl Index {
+    /// Returns the next free id of a given `Category` with the default `AllocationOptions`.
+    ///
+    /// Returns `None` if the category is full.
+    pub fn next_free_id(&self, category: &Category) -> Option<IdNumber> {
+        self.next_free_id_with(category, &AllocationOptions::default())
+    }
+
+    /// Returns the next free id of a given `Category` with given `AllocationOptions`.
+    ///
+    /// Returns `None` if the category is full, or if the highest id is `.99` with
+    /// `Policy::AfterHighest`.
+    pub fn next_free_id_with(&self, category: &Category, options: &AllocationOptions) -> Option<IdNumber> {
+        let used: Vec<IdNumber> = self.ids.range(category.category.range()).map(|(number, _)| *number).collect();
+
+        next_free(category.category.children(), &used, options, |id| id.get_id() == 0)
+    }
+
+    /// Returns the next free category of a given `Area` with the default `AllocationOptions`.
+    ///
+    /// Returns `None` if the area is full.
+    pub fn next_free_category(&self, area: &Area) -> Option<CategoryNumber> {
+        self.next_free_category_with(area, &AllocationOptions::default())
+    }
+
+    /// Returns the next free category of a given `Area` with given `AllocationOptions`.
+    ///
+    /// Returns `None` if the area is full, or if the highest category is `x9` with
+    /// `Policy::AfterHighest`.
+    pub fn next_free_category_with(&self, area: &Area, options: &AllocationOptions) -> Option<CategoryNumber> {
+        let used: Vec<CategoryNumber> = self.categories.range(area.area.range()).map(|(number, _)| *number).collect();
+
+        next_free(area.area.children(), &used, options, |category| u8::from(*category) % 10 == 0)
+    }
+}
+
+/// Returns the next free number of some children with a given policy.
+fn next_free<T: Copy + Ord>(children: impl Iterator<Item = T>, used: &[T], options: &AllocationOptions, reserved: impl Fn(&T) -> bool) -> Option<T> {
+    let highest = used.iter().max();
+
+    children
+        .filter(|child| !(options.skip_reserved && reserved(child)))
+        .filter(|child| match options.policy {
+            Policy::LowestGap => true,
+            Policy::AfterHighest => highest.is_none_or(|highest| child > highest),
+        })
+        .find(|child| !used.contains(child))
+}
@@ -0,0 +1,197 @@
+//! Graphviz DOT and Mermaid diagrams of an `Index`.
+
+use crate::{Category, Index, Step};
+use std::fmt::Write;
+
+/// How deep a diagram goes into the tree of an `Index`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Depth {
+    /// Only areas.
+    Areas,
+
+    /// Areas and their categories.
+    Categories,
+
+    /// Areas, categories, and their ids.
+    #[default]
+    Ids,
+}
+
+/// Options for `Index::to_dot` and `Index::to_mermaid`.
+///
+/// By default, diagrams include every id, without clusters or how full each category is.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::{Depth, DiagramOptions, Index};
+///
+/// let index = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("Invalid index");
+/// let options = DiagramOptions::new().with_depth(Depth::Categories);
+///
+/// assert_eq!(index.to_mermaid(&options), "flowchart LR\n  a1[\"10-19 Area\"]\n  c11[\"11 Category\"]\n  a1 --> c11\n");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DiagramOptions {
+    depth: Depth,
+    clusters: bool,
+    fullness: bool,
+}
+
+impl DiagramOptions {
+    /// Creates the default `DiagramOptions`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the diagram to areas, or to areas and categories.
+    pub fn with_depth(mut self, depth: Depth) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Group each area with its categories and ids in a cluster.
+    pub fn with_clusters(mut self, clusters: bool) -> Self {
+        self.clusters = clusters;
+        self
+    }
+
+    /// Show how many of the 99 ids of each category are used.
+    pub fn with_fullness(mut self, fullness: bool) -> Self {
+        self.fullness = fullness;
+        self
+    }
+}
+
+impl Index {
+    /// Renders the `Index` as a Graphviz DOT graph, with an edge from each entry to its children.
+    ///
+    /// Nodes are named by their number, so `"11.01"` is the node of the id `11.01`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::{DiagramOptions, Index};
+    ///
+    /// let index = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("Invalid index");
+    /// let dot = index.to_dot(&DiagramOptions::new());
+    ///
+    /// assert!(dot.starts_with("digraph Index {\n"));
+    /// assert!(dot.contains("  \"11\" -> \"11.01\";\n"));
+    /// ```
+    pub fn to_dot(&self, options: &DiagramOptions) -> String {
+        let mut dot = String::new();
+        let indent = if options.clusters { "    " } else { "  " };
+
+        let _ = writeln!(dot, "digraph Index {{");
+        let _ = writeln!(dot, "  rankdir=LR;");
+        let _ = writeln!(dot, "  node [shape=box];");
+
+        self.walk(|step| match step {
+            Step::Area(area) => {
+                let number = area.area.to_string();
+                let label = escape_dot(&area.to_string());
+
+                if options.clusters {
+                    let _ = writeln!(dot, "  subgraph \"cluster_{number}\" {{");
+                    let _ = writeln!(dot, "    label=\"{label}\";");
+                }
+
+                let _ = writeln!(dot, "{indent}\"{number}\" [label=\"{label}\"];");
+            }
+
+            Step::Category(category) if options.depth != Depth::Areas => {
+                let mut label = escape_dot(&category.to_string());
+
+                if options.fullness {
+                    let _ = write!(label, "\\n{}", self.fullness(category));
+                }
+
+                let _ = writeln!(dot, "{indent}\"{}\" [label=\"{label}\"];", category.category);
+                let _ = writeln!(dot, "{indent}\"{}\" -> \"{}\";", category.area, category.category);
+            }
+
+            Step::Id(id) if options.depth == Depth::Ids => {
+                let _ = writeln!(dot, "{indent}\"{}\" [label=\"{}\"];", id.id, escape_dot(&id.to_string()));
+                let _ = writeln!(dot, "{indent}\"{}\" -> \"{}\";", id.category, id.id);
+            }
+
+            Step::Leave if options.clusters => {
+                let _ = writeln!(dot, "  }}");
+            }
+
+            _ => {}
+        });
+
+        let _ = writeln!(dot, "}}");
+
+        dot
+    }
+
+    /// Renders the `Index` as a Mermaid flowchart, with an arrow from each entry to its children.
+    ///
+    /// Nodes are named `a1` for the area `10-19`, `c11` for the category `11`, and `i11_01` for
+    /// the id `11.01`, since Mermaid doesn't allow dashes or dots in names. Clusters are subgraphs.
+    pub fn to_mermaid(&self, options: &DiagramOptions) -> String {
+        let mut mermaid = String::new();
+        let indent = if options.clusters { "    " } else { "  " };
+
+        let _ = writeln!(mermaid, "flowchart LR");
+
+        self.walk(|step| match step {
+            Step::Area(area) => {
+                let name = format!("a{}", u8::from(area.area));
+                let label = escape_mermaid(&area.to_string());
+
+                if options.clusters {
+                    let _ = writeln!(mermaid, "  subgraph {name}_cluster [\"{label}\"]");
+                }
+
+                let _ = writeln!(mermaid, "{indent}{name}[\"{label}\"]");
+            }
+
+            Step::Category(category) if options.depth != Depth::Areas => {
+                let mut label = escape_mermaid(&category.to_string());
+
+                if options.fullness {
+                    let _ = write!(label, "<br>{}", self.fullness(category));
+                }
+
+                let _ = writeln!(mermaid, "{indent}c{}[\"{label}\"]", category.category);
+                let _ = writeln!(mermaid, "{indent}a{} --> c{}", u8::from(category.area), category.category);
+            }
+
+            Step::Id(id) if options.depth == Depth::Ids => {
+                let name = id.id.to_string().replace('.', "_");
+
+                let _ = writeln!(mermaid, "{indent}i{name}[\"{}\"]", escape_mermaid(&id.to_string()));
+                let _ = writeln!(mermaid, "{indent}c{} --> i{name}", id.category);
+            }
+
+            Step::Leave if options.clusters => {
+                let _ = writeln!(mermaid, "  end");
+            }
+
+            _ => {}
+        });
+
+        mermaid
+    }
+
+    /// Returns how many of the 99 ids of a category are used, like `3/99 ids`.
+    fn fullness(&self, category: &Category) -> String {
+        let used = self.ids.range(category.category.range()).filter(|(number, _)| number.get_id() != 0).count();
+
+        format!("{used}/99 ids")
+    }
+}
+
+/// Escapes the characters of some text that have a meaning in a quoted DOT string.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes the characters of some text that have a meaning in a quoted Mermaid label.
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
@@ -0,0 +1,152 @@
+//! Structural differences between two `Index` values.
+
+use crate::{Entry, Index};
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::discriminant;
+
+/// A change between two `Index` values, from `Index::diff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// An entry only in the other `Index`.
+    Added(Entry),
+
+    /// An entry only in this `Index`.
+    Removed(Entry),
+
+    /// An entry with the same number and a different title.
+    Renamed {
+        /// The entry in this `Index`.
+        from: Entry,
+        /// The entry in the other `Index`.
+        to: Entry,
+    },
+
+    /// An entry with the same title and a different number.
+    Moved {
+        /// The entry in this `Index`.
+        from: Entry,
+        /// The entry in the other `Index`.
+        to: Entry,
+    },
+}
+
+/// The changes between two `Index` values, in the order of their entries.
+///
+/// The `Display` implementation is a report like a unified diff, with a `@@` header per change.
+///
+/// ```text
+/// @@ renamed 11.01 @@
+/// -11.01 Old title
+/// +11.01 New title
+/// @@ moved 11.02 to 12.01 @@
+/// -11.02 Title
+/// +12.01 Title
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Index {
+    /// Returns the changes from this `Index` to another.
+    ///
+    /// Entries are matched by number first. Those left over are matched by title, so an entry
+    /// that keeps its title but gets a new number is `Change::Moved` instead of being removed and
+    /// added. Metadata and comments aren't compared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::{Change, Index};
+    ///
+    /// let before = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("Invalid index");
+    /// let after = Index::new("10-19 Area\n11 Renamed\n11.02 Id").expect("Invalid index");
+    /// let diff = before.diff(&after);
+    ///
+    /// assert!(matches!(diff.get_changes()[0], Change::Renamed { .. }));
+    /// assert!(matches!(diff.get_changes()[1], Change::Moved { .. }));
+    /// assert_eq!(diff.to_string(), "@@ renamed 11 @@\n-11 Category\n+11 Renamed\n@@ moved 11.01 to 11.02 @@\n-11.01 Id\n+11.02 Id");
+    /// ```
+    pub fn diff(&self, other: &Index) -> Diff {
+        let entries = self.entries();
+        let others = other.entries();
+
+        let numbers: HashMap<String, &Entry> = entries.iter().map(|entry| (entry.get_number(), entry)).collect();
+        let other_numbers: HashMap<String, &Entry> = others.iter().map(|entry| (entry.get_number(), entry)).collect();
+
+        let mut changes = vec![];
+        let mut removed = vec![];
+
+        for entry in &entries {
+            match other_numbers.get(&entry.get_number()) {
+                Some(to) if to.get_name() != entry.get_name() => changes.push(Change::Renamed {
+                    from: entry.clone(),
+                    to: (*to).clone(),
+                }),
+                Some(_) => {}
+                None => removed.push(entry),
+            }
+        }
+
+        let mut added: Vec<&Entry> = others.iter().filter(|entry| !numbers.contains_key(&entry.get_number())).collect();
+
+        for entry in removed {
+            let moved = added.iter().position(|to| discriminant(*to) == discriminant(entry) && to.get_name() == entry.get_name());
+
+            match moved {
+                Some(i) => changes.push(Change::Moved {
+                    from: entry.clone(),
+                    to: added.remove(i).clone(),
+                }),
+                None => changes.push(Change::Removed(entry.clone())),
+            }
+        }
+
+        changes.extend(added.into_iter().cloned().map(Change::Added));
+        changes.sort_by_key(|change| change.get_entry().key());
+
+        Diff { changes }
+    }
+}
+
+impl Change {
+    /// Returns the entry the change is about, which is the entry in this `Index` unless added.
+    pub fn get_entry(&self) -> &Entry {
+        match self {
+            Self::Added(entry) | Self::Removed(entry) => entry,
+            Self::Renamed { from, .. } | Self::Moved { from, .. } => from,
+        }
+    }
+}
+
+impl Diff {
+    /// Returns the changes, in the order of their entries.
+    pub fn get_changes(&self) -> &Vec<Change> {
+        &self.changes
+    }
+
+    /// Returns true if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added(entry) => write!(f, "@@ added {} @@\n+{entry}", entry.get_number()),
+            Self::Removed(entry) => write!(f, "@@ removed {} @@\n-{entry}", entry.get_number()),
+            Self::Renamed { from, to } => write!(f, "@@ renamed {} @@\n-{from}\n+{to}", from.get_number()),
+            Self::Moved { from, to } => write!(f, "@@ moved {} to {} @@\n-{from}\n+{to}", from.get_number(), to.get_number()),
+        }
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let changes: Vec<String> = self.changes.iter().map(Change::to_string).collect();
+
+        write!(f, "{}", changes.join("\n"))
+    }
+}
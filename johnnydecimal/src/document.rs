@@ -0,0 +1,407 @@
+//! A lossless syntax tree for `00.00 Index.txt`.
+
+use crate::lexer::split_comments;
+use crate::{Area, AreaNumber, Category, CategoryNumber, Comments, Error, Id, IdNumber, Index, Metadata};
+use sanitise_file_name::sanitise;
+use std::fmt;
+
+/// `00.00 Index.txt`, byte for byte.
+///
+/// Unlike an `Index`, a `Document` keeps every line of its source, including blank lines,
+/// indentation, comments, and the order chosen by the user. Changes made through its methods
+/// only touch the lines they affect, so unchanged regions are written back exactly as they were.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::Document;
+///
+/// let source = "10-19 Area\n\n   11 Category // Notes\n";
+/// let mut document = Document::new(source).expect("Invalid document");
+///
+/// assert_eq!(document.to_string(), source);
+///
+/// document.rename("11", "Renamed").expect("Invalid title");
+///
+/// assert_eq!(document.to_string(), "10-19 Area\n\n   11 Renamed // Notes\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    nodes: Vec<Node>,
+}
+
+/// A single line of a `Document`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    kind: NodeKind,
+    text: String,
+    ending: String,
+    content: Option<(usize, usize)>,
+    key: Option<Key>,
+}
+
+/// The kind of content on a `Node`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// An empty line or a line with only white space.
+    Blank,
+    /// A line with only comments, or the inside of a block comment.
+    Comment,
+    /// `10-19 Area`
+    Area,
+    /// `11 Category`
+    Category,
+    /// `11.01 Id`
+    Id,
+    /// `- key: value`
+    Metadata,
+    /// A line that is none of the above.
+    Unknown,
+}
+
+/// The position of an entry in a sorted index: `(area, category, id)`.
+type Key = (AreaNumber, Option<CategoryNumber>, Option<IdNumber>);
+
+impl Document {
+    /// Creates a new `Document` from a given `&str`, returning a `Result`.
+    ///
+    /// Lines that aren't valid entries are kept as `NodeKind::Unknown`, so any text without an
+    /// unclosed block comment can be read. Use `get_index` to validate the contents.
+    pub fn new(str: &str) -> Result<Self, Error> {
+        Ok(Self { nodes: parse_nodes(str)? })
+    }
+
+    /// Returns the lines of the `Document` in order.
+    pub fn get_nodes(&self) -> &Vec<Node> {
+        &self.nodes
+    }
+
+    /// Returns the validated `Index` of the `Document`.
+    pub fn get_index(&self) -> Result<Index, Error> {
+        Index::new(&self.to_string())
+    }
+
+    /// Changes the title of the area, category, or id with a given number.
+    ///
+    /// Only the title is replaced. Indentation and comments on the line are kept.
+    pub fn rename(&mut self, number: &str, name: &str) -> Result<&Self, Error> {
+        if sanitise(name) != name || name.is_empty() {
+            return Err(Error::InvalidTitle(name.to_string()));
+        }
+
+        let position = self.find(number).ok_or_else(|| Error::NotFound(number.to_string()))?;
+        let node = &mut self.nodes[position];
+        let (start, end) = node.content.ok_or(Error::NotEditable { line: position + 1 })?;
+        let title = start + number.len() + 1;
+
+        node.text.replace_range(title..end, name);
+        node.content = Some((start, title + name.len()));
+
+        Ok(self)
+    }
+
+    /// Sets the `value` of a metadata `key` for the area, category, or id with a given number.
+    ///
+    /// An existing `- key: value` line is edited in place. Otherwise a new line is added after
+    /// the existing metadata of the entry.
+    pub fn set_metadata(&mut self, number: &str, key: &str, value: &str) -> Result<&Self, Error> {
+        Metadata::default().set(key, value)?;
+
+        let position = self.find(number).ok_or_else(|| Error::NotFound(number.to_string()))?;
+        let line = if value.is_empty() { format!("- {key}:") } else { format!("- {key}: {value}") };
+
+        if let Some(existing) = self.find_metadata(position, key) {
+            let node = &mut self.nodes[existing];
+            let (start, end) = node.content.ok_or(Error::NotEditable { line: existing + 1 })?;
+
+            node.text.replace_range(start..end, &line);
+            node.content = Some((start, start + line.len()));
+
+            return Ok(self);
+        }
+
+        let end = self.block_end(position);
+        let last = &self.nodes[end - 1];
+        let indent = last.indent().to_string();
+
+        self.insert_lines(end, &[format!("{indent}{line}")])?;
+
+        Ok(self)
+    }
+
+    /// Removes a metadata `key` from the area, category, or id with a given number.
+    pub fn remove_metadata(&mut self, number: &str, key: &str) -> Result<&Self, Error> {
+        let position = self.find(number).ok_or_else(|| Error::NotFound(number.to_string()))?;
+        let existing = self.find_metadata(position, key).ok_or_else(|| Error::NotFound(format!("{number} {key}")))?;
+
+        self.remove_lines(existing, existing + 1);
+
+        Ok(self)
+    }
+
+    /// Adds a new `Area` with its comments and metadata after the entries that sort before it.
+    pub fn add_area(&mut self, area: &Area) -> Result<&Self, Error> {
+        let key = (area.get_area(), None, None);
+
+        self.add_entry(key, NodeKind::Area, &area.to_string(), &area.metadata, &area.comments)
+    }
+
+    /// Adds a new `Category` with its comments and metadata after the entries that sort before it.
+    pub fn add_category(&mut self, category: &Category) -> Result<&Self, Error> {
+        let key = (category.get_area(), Some(category.get_category()), None);
+
+        self.add_entry(key, NodeKind::Category, &category.to_string(), &category.metadata, &category.comments)
+    }
+
+    /// Adds a new `Id` with its comments and metadata after the entries that sort before it.
+    pub fn add_id(&mut self, id: &Id) -> Result<&Self, Error> {
+        let key = (id.get_area(), Some(id.get_category()), Some(id.get_id()));
+
+        self.add_entry(key, NodeKind::Id, &id.to_string(), &id.metadata, &id.comments)
+    }
+
+    /// Removes the lines of an area, category, or id, along with its metadata and children.
+    ///
+    /// Comments above the entry are kept.
+    pub fn remove(&mut self, number: &str) -> Result<&Self, Error> {
+        let position = self.find(number).ok_or_else(|| Error::NotFound(number.to_string()))?;
+        let key = self.nodes[position].key.ok_or_else(|| Error::NotFound(number.to_string()))?;
+        let mut last = position;
+
+        for (i, node) in self.nodes.iter().enumerate().skip(position + 1) {
+            match &node.key {
+                Some(child) if is_descendant(child, &key) => last = i,
+                Some(_) => break,
+                None if node.kind == NodeKind::Unknown => break,
+                None => {}
+            }
+        }
+
+        let end = self.block_end(last);
+
+        self.remove_lines(position, end);
+
+        Ok(self)
+    }
+
+    /// Returns the position of the entry with a given number.
+    fn find(&self, number: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| node.get_number().as_deref() == Some(number))
+    }
+
+    /// Returns the position of the `- key: value` line of a given entry.
+    fn find_metadata(&self, position: usize, key: &str) -> Option<usize> {
+        (position + 1..self.block_end(position)).find(|&i| {
+            let node = &self.nodes[i];
+
+            node.kind == NodeKind::Metadata && crate::parse_metadata(node.text.trim()).is_ok_and(|(k, _)| k == key)
+        })
+    }
+
+    /// Returns the position after the last metadata line of a given entry.
+    fn block_end(&self, position: usize) -> usize {
+        let mut end = position + 1;
+
+        for (i, node) in self.nodes.iter().enumerate().skip(position + 1) {
+            match node.kind {
+                NodeKind::Metadata => end = i + 1,
+                NodeKind::Blank | NodeKind::Comment => {}
+                _ => break,
+            }
+        }
+
+        end
+    }
+
+    /// Adds the lines of a new entry, keeping the indentation of similar entries.
+    fn add_entry(&mut self, key: Key, kind: NodeKind, line: &str, metadata: &Metadata, comments: &Comments) -> Result<&Self, Error> {
+        if let Some(position) = self.nodes.iter().position(|node| node.key.as_ref() == Some(&key)) {
+            return Err(Error::Duplicate {
+                number: self.nodes[position].get_number().unwrap_or_default(),
+                line: Some(position + 1),
+            });
+        }
+
+        let previous = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.key.as_ref().is_some_and(|k| *k < key))
+            .max_by(|(_, a), (_, b)| a.key.cmp(&b.key));
+
+        let position = match previous {
+            Some((i, _)) => self.block_end(i),
+            None => self.nodes.iter().position(|node| node.key.is_some()).unwrap_or(self.nodes.len()),
+        };
+
+        let indent = self
+            .nodes
+            .iter()
+            .find(|node| node.kind == kind)
+            .or_else(|| self.nodes.iter().find(|node| node.key.as_ref().is_some_and(|k| is_descendant(&key, k))))
+            .map_or("", |node| node.indent())
+            .to_string();
+
+        let mut lines: Vec<String> = comments.get_above().iter().map(|comment| format!("{indent}{comment}")).collect();
+
+        match comments.get_inline() {
+            Some(comment) => lines.push(format!("{indent}{line} {comment}")),
+            None => lines.push(format!("{indent}{line}")),
+        }
+
+        lines.extend(crate::metadata_lines(metadata, comments).iter().map(|pair| format!("{indent}{pair}")));
+
+        self.insert_lines(position, &lines)?;
+
+        Ok(self)
+    }
+
+    /// Inserts complete lines at a given position, using the line ending of the document.
+    fn insert_lines(&mut self, position: usize, lines: &[String]) -> Result<(), Error> {
+        let ending = self.nodes.iter().map(|node| node.ending.as_str()).find(|ending| !ending.is_empty()).unwrap_or("\n").to_string();
+        let unterminated = position == self.nodes.len() && self.nodes.last().is_some_and(|node| node.ending.is_empty());
+
+        if unterminated {
+            if let Some(last) = self.nodes.last_mut() {
+                last.ending = ending.clone();
+            }
+        }
+
+        let mut fragment: String = lines.iter().map(|line| format!("{line}{ending}")).collect();
+
+        if unterminated {
+            fragment.truncate(fragment.len() - ending.len());
+        }
+
+        let nodes = parse_nodes(&fragment)?;
+
+        self.nodes.splice(position..position, nodes);
+
+        Ok(())
+    }
+
+    /// Removes the lines in a given range, keeping the last line unterminated if it was before.
+    fn remove_lines(&mut self, start: usize, end: usize) {
+        let unterminated = end == self.nodes.len() && self.nodes.last().is_some_and(|node| node.ending.is_empty());
+
+        self.nodes.drain(start..end);
+
+        if unterminated {
+            if let Some(last) = self.nodes.last_mut() {
+                last.ending.clear();
+            }
+        }
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            write!(f, "{}{}", node.text, node.ending)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Node {
+    /// Returns the kind of content on the line.
+    pub fn get_kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    /// Returns the raw text of the line without its line ending.
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the line ending of the line: `\n`, `\r\n`, or empty for the last line.
+    pub fn get_ending(&self) -> &str {
+        &self.ending
+    }
+
+    /// Returns the number `10-19`, `11`, or `11.01` if the line is an area, category, or id.
+    pub fn get_number(&self) -> Option<String> {
+        match self.key? {
+            (_, _, Some(id)) => Some(id.to_string()),
+            (_, Some(category), None) => Some(category.to_string()),
+            (area, None, None) => Some(area.to_string()),
+        }
+    }
+
+    /// Returns the title of the line if it's an area, category, or id not interrupted by a comment.
+    pub fn get_name(&self) -> Option<&str> {
+        let (start, end) = self.content?;
+        let number = self.get_number()?;
+
+        Some(&self.text[start + number.len() + 1..end])
+    }
+
+    /// Returns the white space before the content of the line.
+    fn indent(&self) -> &str {
+        &self.text[..self.text.len() - self.text.trim_start().len()]
+    }
+}
+
+/// Parses lines into `Node`s.
+fn parse_nodes(str: &str) -> Result<Vec<Node>, Error> {
+    let mut nodes = vec![];
+
+    for line in split_comments(str)? {
+        let content = line.content.trim();
+        let (kind, key) = classify(content);
+
+        let kind = match kind {
+            NodeKind::Blank if !line.text.trim().is_empty() => NodeKind::Comment,
+            kind => kind,
+        };
+
+        nodes.push(Node {
+            kind,
+            text: line.text.to_string(),
+            ending: line.ending.to_string(),
+            content: line.start.map(|start| (start, start + content.len())),
+            key,
+        });
+    }
+
+    Ok(nodes)
+}
+
+/// Returns the kind of a given line content, and its key if it's an entry.
+fn classify(content: &str) -> (NodeKind, Option<Key>) {
+    if content.is_empty() {
+        return (NodeKind::Blank, None);
+    }
+
+    if content.starts_with('-') {
+        return (NodeKind::Metadata, None);
+    }
+
+    if let Ok(id) = Id::new(content) {
+        let key = (id.get_area(), Some(id.get_category()), Some(id.get_id()));
+
+        return (NodeKind::Id, Some(key));
+    }
+
+    if let Ok(category) = Category::new(content) {
+        let key = (category.get_area(), Some(category.get_category()), None);
+
+        return (NodeKind::Category, Some(key));
+    }
+
+    if let Ok(area) = Area::new(content) {
+        return (NodeKind::Area, Some((area.get_area(), None, None)));
+    }
+
+    (NodeKind::Unknown, None)
+}
+
+/// Returns `true` if `child` is a category or id below `parent`.
+fn is_descendant(child: &Key, parent: &Key) -> bool {
+    match parent {
+        (area, None, None) => child.0 == *area && child.1.is_some(),
+        (area, Some(category), None) => child.0 == *area && child.1 == Some(*category) && child.2.is_some(),
+        _ => false,
+    }
+}
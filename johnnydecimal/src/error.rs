@@ -0,0 +1,231 @@
+//! Errors returned when parsing and validating Johnny.Decimal systems.
+
+use std::fmt;
+
+/// An error returned by this crate.
+///
+/// The `Display` implementation is a friendly message you can show to your users.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::{Error, Expected, Index};
+///
+/// let Err(Error::Parse(error)) = Index::new("10-19 Area\n   11Category") else {
+///     panic!("Should fail to parse");
+/// };
+///
+/// assert_eq!(error.get_line(), 2);
+/// assert_eq!(error.get_column(), 6);
+/// assert_eq!(error.get_expected(), Expected::Category);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Some text doesn't follow the expected form.
+    Parse(ParseError),
+    /// A title can't be used as a file name.
+    InvalidTitle(String),
+    /// A metadata key or value isn't valid.
+    InvalidMetadata(&'static str),
+    /// A comment isn't a single `// line` or `/* block */` comment.
+    InvalidComment(&'static str),
+    /// An area, category, or id with the same number already exists.
+    Duplicate {
+        /// The number `10-19`, `11`, or `11.01` that exists twice.
+        number: String,
+        /// The line of the duplicate, if read from an index.
+        line: Option<usize>,
+    },
+    /// A metadata key exists twice for the same entry.
+    DuplicateMetadata {
+        /// The key that exists twice.
+        key: String,
+        /// The line of the duplicate, if read from an index.
+        line: Option<usize>,
+    },
+    /// A category or id has no parent.
+    Orphan {
+        /// The number `11` or `11.01` without a parent.
+        number: String,
+        /// The number `10-19` or `11` of the missing parent.
+        parent: String,
+        /// The line of the orphan, if read from an index.
+        line: Option<usize>,
+    },
+    /// An area, category, or id comes before an entry with a lower number.
+    OutOfOrder {
+        /// The number `10-19`, `11`, or `11.01` that is out of order.
+        number: String,
+        /// The number of the higher entry read before it.
+        previous: String,
+        /// The line of the entry, if read from an index.
+        line: Option<usize>,
+    },
+    /// No area, category, or id with a given number exists.
+    NotFound(String),
+    /// An area or category has no number left for another category or id.
+    Full(String),
+    /// Inserting would shift the last category `x9` or id `.99` past the end of its parent.
+    Overflow(String),
+    /// An index couldn't be imported from another format.
+    Import {
+        /// The name of the format, such as `JSON`.
+        format: &'static str,
+        /// The line or row where importing failed, if known.
+        line: Option<usize>,
+        /// A friendly description of what went wrong.
+        reason: String,
+    },
+    /// A line is interrupted by a comment, so it can't be edited without losing the comment.
+    NotEditable {
+        /// The line that can't be edited.
+        line: usize,
+    },
+}
+
+/// Where and why some text couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    text: String,
+    expected: Expected,
+    reason: &'static str,
+}
+
+/// The form some text was expected to follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// `a0-a9 <title>`
+    Area,
+    /// `ac <title>`
+    Category,
+    /// `ac.id <title>`
+    Id,
+    /// An area, category, or id.
+    Entry,
+    /// `a0-a9`
+    AreaNumber,
+    /// `ac`
+    CategoryNumber,
+    /// `ac.id`
+    IdNumber,
+    /// `- key: value`
+    Metadata,
+    /// The `*/` that closes a block comment.
+    CommentEnd,
+}
+
+impl ParseError {
+    /// Creates a new `ParseError` on line 1 of a given `text`.
+    pub(crate) fn new(text: &str, column: usize, expected: Expected, reason: &'static str) -> Self {
+        Self {
+            line: 1,
+            column,
+            text: text.to_string(),
+            expected,
+            reason,
+        }
+    }
+
+    /// Moves the error to a given `line`, shifting its column by the characters before `text`.
+    pub(crate) fn at(mut self, line: usize, offset: usize) -> Self {
+        self.line = line;
+        self.column += offset;
+        self
+    }
+
+    /// Line `1`: The 1-based line of the text that failed to parse.
+    pub fn get_line(&self) -> usize {
+        self.line
+    }
+
+    /// Column `1`: The 1-based character in the line where parsing failed.
+    pub fn get_column(&self) -> usize {
+        self.column
+    }
+
+    /// The text that failed to parse.
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// The form that the text was expected to follow.
+    pub fn get_expected(&self) -> Expected {
+        self.expected
+    }
+
+    /// A friendly description of what went wrong.
+    pub fn get_reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl Error {
+    /// Returns the 1-based line of the error, if it was read from an index.
+    pub fn get_line(&self) -> Option<usize> {
+        match self {
+            Self::Parse(error) => Some(error.line),
+            Self::Duplicate { line, .. } | Self::DuplicateMetadata { line, .. } | Self::Orphan { line, .. } | Self::OutOfOrder { line, .. } | Self::Import { line, .. } => *line,
+            Self::NotEditable { line } => Some(*line),
+            _ => None,
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "{error}"),
+            Self::InvalidTitle(title) => write!(f, "`{title}` is not a valid title for file names"),
+            Self::InvalidMetadata(reason) | Self::InvalidComment(reason) => write!(f, "{reason}"),
+            Self::Duplicate { number, line } => write!(f, "{}Duplicate `{number}` is not allowed", prefix(*line)),
+            Self::DuplicateMetadata { key, line } => write!(f, "{}Duplicate metadata key `{key}` is not allowed", prefix(*line)),
+            Self::Orphan { number, parent, line } => write!(f, "{}`{number}` has no parent `{parent}`", prefix(*line)),
+            Self::OutOfOrder { number, previous, line } => write!(f, "{}`{number}` should come before `{previous}`", prefix(*line)),
+            Self::NotFound(number) => write!(f, "`{number}` doesn't exist"),
+            Self::Full(number) => write!(f, "`{number}` has no free numbers left"),
+            Self::Overflow(number) => write!(f, "Inserting would shift `{number}` past the end of its parent"),
+            Self::Import { format, reason, .. } => write!(f, "Invalid {format}: {reason}"),
+            Self::NotEditable { line } => write!(f, "Line {line} is interrupted by a comment and can't be edited"),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}, column {}: {} (expected `{}`): `{}`", self.line, self.column, self.reason, self.expected, self.text)
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Area => write!(f, "a0-a9 <title>"),
+            Self::Category => write!(f, "ac <title>"),
+            Self::Id => write!(f, "ac.id <title>"),
+            Self::Entry => write!(f, "a0-a9, ac, or ac.id <title>"),
+            Self::AreaNumber => write!(f, "a0-a9"),
+            Self::CategoryNumber => write!(f, "ac"),
+            Self::IdNumber => write!(f, "ac.id"),
+            Self::Metadata => write!(f, "- key: value"),
+            Self::CommentEnd => write!(f, "*/"),
+        }
+    }
+}
+
+/// Returns `Line n: ` if a line is known.
+fn prefix(line: Option<usize>) -> String {
+    line.map_or(String::new(), |line| format!("Line {line}: "))
+}
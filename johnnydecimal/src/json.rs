@@ -0,0 +1,183 @@
+//! JSON import and export of an `Index` as a nested tree, enabled by the `json` feature.
+
+use crate::serialization::{AreaObject, CategoryObject, IdObject};
+use crate::{Area, Category, Error, Id, Index};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// An area with its categories.
+#[derive(Serialize, Deserialize)]
+struct AreaNode {
+    #[serde(flatten)]
+    area: AreaObject,
+    #[serde(default)]
+    categories: Vec<CategoryNode>,
+    /// `deny_unknown_fields` doesn't reach flattened fields, so unknown ones are caught here.
+    #[serde(flatten, skip_serializing)]
+    unknown: BTreeMap<String, IgnoredAny>,
+}
+
+/// A category with its ids.
+#[derive(Serialize, Deserialize)]
+struct CategoryNode {
+    #[serde(flatten)]
+    category: CategoryObject,
+    #[serde(default)]
+    ids: Vec<IdObject>,
+    #[serde(flatten, skip_serializing)]
+    unknown: BTreeMap<String, IgnoredAny>,
+}
+
+/// The root of the tree.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Tree {
+    #[serde(default)]
+    areas: Vec<AreaNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trailing_comments: Vec<String>,
+}
+
+impl Index {
+    /// Exports the `Index` as a nested tree of JSON.
+    ///
+    /// Areas contain their categories, and categories contain their ids. `metadata` is an object
+    /// of keys to values, and `comments` holds the comments `above` and `inline` of an entry, and
+    /// the inline comments of its `metadata` keys. Both are left out if empty.
+    ///
+    /// ```json
+    /// {
+    ///   "areas": [
+    ///     {
+    ///       "area": "10-19",
+    ///       "name": "Area",
+    ///       "categories": [
+    ///         {
+    ///           "category": "11",
+    ///           "name": "Category",
+    ///           "metadata": { "owner": "Alice" },
+    ///           "ids": [{ "id": "11.01", "name": "Id", "comments": { "inline": "// Notes" } }]
+    ///         }
+    ///       ]
+    ///     }
+    ///   ],
+    ///   "trailing_comments": ["// End"]
+    /// }
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::Index;
+    ///
+    /// let index = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("Invalid index");
+    /// let json = index.to_json();
+    ///
+    /// assert!(json.contains(r#""id": "11.01""#));
+    /// assert_eq!(Index::from_json(&json).expect("Invalid JSON"), index);
+    /// ```
+    pub fn to_json(&self) -> String {
+        let areas = self
+            .areas
+            .values()
+            .map(|area| AreaNode {
+                area: area.into(),
+                categories: self
+                    .derive_categories_of_area(area)
+                    .iter()
+                    .map(|category| CategoryNode {
+                        category: category.into(),
+                        ids: self.derive_ids_of_category(category).iter().map(IdObject::from).collect(),
+                        unknown: BTreeMap::new(),
+                    })
+                    .collect(),
+                unknown: BTreeMap::new(),
+            })
+            .collect();
+
+        let tree = Tree {
+            areas,
+            trailing_comments: self.trailing_comments.clone(),
+        };
+
+        serde_json::to_string_pretty(&tree).expect("should serialize, since every key is a string")
+    }
+
+    /// Imports an `Index` from a nested tree of JSON, in the shape written by `to_json`.
+    ///
+    /// The tree is checked with the same rules as `Index::with_vecs`. Every category must be
+    /// nested in its own area, and every id in its own category.
+    pub fn from_json(str: &str) -> Result<Self, Error> {
+        let tree: Tree = serde_json::from_str(str).map_err(|error| Error::Import {
+            format: "JSON",
+            line: Some(error.line()),
+            reason: error.to_string(),
+        })?;
+
+        let mut areas = vec![];
+        let mut categories = vec![];
+        let mut ids = vec![];
+
+        for node in tree.areas {
+            unknown(&node.unknown)?;
+
+            let area = Area::try_from(node.area)?;
+
+            for node in node.categories {
+                unknown(&node.unknown)?;
+
+                let category = Category::try_from(node.category)?;
+
+                if category.area != area.area {
+                    return Err(misplaced(category.category, area.area));
+                }
+
+                for node in node.ids {
+                    let id = Id::try_from(node)?;
+
+                    if id.category != category.category {
+                        return Err(misplaced(id.id, category.category));
+                    }
+
+                    ids.push(id);
+                }
+
+                categories.push(category);
+            }
+
+            areas.push(area);
+        }
+
+        for comment in &tree.trailing_comments {
+            crate::validate_comment(comment)?;
+        }
+
+        let mut index = Self::with_vecs(&areas, &categories, &ids)?;
+
+        index.trailing_comments = tree.trailing_comments;
+
+        Ok(index)
+    }
+}
+
+/// Creates an `Error::Import` for an entry nested in the wrong parent.
+fn misplaced(number: impl std::fmt::Display, parent: impl std::fmt::Display) -> Error {
+    Error::Import {
+        format: "JSON",
+        line: None,
+        reason: format!("`{number}` is nested in `{parent}`, which is not its parent"),
+    }
+}
+
+/// Fails with an `Error::Import` if a node has a field that isn't known.
+fn unknown(fields: &BTreeMap<String, IgnoredAny>) -> Result<(), Error> {
+    match fields.keys().next() {
+        Some(field) => Err(Error::Import {
+            format: "JSON",
+            line: None,
+            reason: format!("unknown field `{field}`"),
+        }),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,133 @@
+//! Splits an index into lines, separating comments from content.
+
+use crate::{Expected, ParseError};
+
+/// A line of an index with its comments separated from its content.
+#[derive(Debug, Default)]
+pub(crate) struct Line<'a> {
+    /// The raw text of the line without its line ending.
+    pub text: &'a str,
+    /// The line ending of the line: `\n`, `\r\n`, or empty for the last line.
+    pub ending: &'a str,
+    /// The text of the line with all comments removed.
+    pub content: String,
+    /// The byte offset of the trimmed `content` in `text`, if it isn't interrupted by a comment.
+    pub start: Option<usize>,
+    /// The comments that start on this line.
+    pub comments: Vec<String>,
+}
+
+/// Splits a given index `&str` into lines, separating comments from content.
+///
+/// `//` and `/*` only start a comment at the start of a line or after white space, which keeps
+/// metadata values such as `https://example.com` intact. Block comments that span several lines
+/// are attached to the line they start on.
+pub(crate) fn split_comments(str: &str) -> Result<Vec<Line<'_>>, ParseError> {
+    let mut lines: Vec<Line> = vec![];
+    let mut block: Option<(usize, usize, String)> = None;
+
+    for raw in str.split_inclusive('\n') {
+        let text = raw.strip_suffix('\n').map_or(raw, |text| text.strip_suffix('\r').unwrap_or(text));
+        let mut line = Line {
+            text,
+            ending: &raw[text.len()..],
+            ..Default::default()
+        };
+        let mut segments: Vec<(usize, &str)> = vec![];
+        let mut offset = 0;
+
+        while offset < text.len() {
+            let rest = &text[offset..];
+
+            if let Some((start, column, mut comment)) = block.take() {
+                let Some(end) = rest.find("*/") else {
+                    comment.push_str(rest);
+                    block = Some((start, column, comment));
+                    break;
+                };
+
+                comment.push_str(&rest[..end + 2]);
+                offset += end + 2;
+
+                if start == lines.len() {
+                    line.comments.push(comment);
+                } else {
+                    lines[start].comments.push(comment);
+                }
+
+                continue;
+            }
+
+            let Some((position, is_block)) = find_comment(rest) else {
+                segments.push((offset, rest));
+                break;
+            };
+
+            segments.push((offset, &rest[..position]));
+
+            if is_block {
+                block = Some((lines.len(), text[..offset + position].chars().count() + 1, "/*".to_string()));
+                offset += position + 2;
+            } else {
+                line.comments.push(rest[position..].trim_end().to_string());
+                break;
+            }
+        }
+
+        if let Some((_, _, comment)) = block.as_mut() {
+            comment.push('\n');
+        }
+
+        line.content = segments.iter().map(|(_, segment)| *segment).collect();
+        line.start = find_start(text, &segments, line.content.trim());
+
+        lines.push(line);
+    }
+
+    if let Some((start, column, _)) = block {
+        let text = lines[start].text;
+
+        return Err(ParseError::new(text, column, Expected::CommentEnd, "A block comment was opened with /* but never closed with */").at(start + 1, 0));
+    }
+
+    Ok(lines)
+}
+
+impl Line<'_> {
+    /// Returns the number of characters before the content of the line.
+    pub fn offset(&self) -> usize {
+        match self.start {
+            Some(start) => self.text[..start].chars().count(),
+            None => self.content.chars().take_while(|c| c.is_whitespace()).count(),
+        }
+    }
+}
+
+/// Returns the byte position of the first comment in a given `&str` and whether it is a block comment.
+pub(crate) fn find_comment(str: &str) -> Option<(usize, bool)> {
+    for (position, _) in str.match_indices('/') {
+        let is_block = match str[position + 1..].chars().next() {
+            Some('/') => false,
+            Some('*') => true,
+            _ => continue,
+        };
+
+        if position == 0 || str[..position].ends_with(char::is_whitespace) {
+            return Some((position, is_block));
+        }
+    }
+
+    None
+}
+
+/// Returns the byte offset of `trimmed` in `text` if it appears there without interruption.
+fn find_start(text: &str, segments: &[(usize, &str)], trimmed: &str) -> Option<usize> {
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let (offset, segment) = segments.iter().find(|(_, segment)| !segment.trim().is_empty())?;
+    let start = offset + (segment.len() - segment.trim_start().len());
+
+    (text.get(start..start + trimmed.len()) == Some(trimmed)).then_some(start)
+}
@@ -2,8 +2,40 @@
 
 use sanitise_file_name::sanitise;
 use std::cmp::Ordering;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
+use std::str::FromStr;
+
+mod allocation;
+mod diagram;
+mod diff;
+mod document;
+mod error;
+#[cfg(feature = "json")]
+mod json;
+mod lexer;
+mod merge;
+mod number;
+#[cfg(feature = "opml")]
+mod opml;
+mod render;
+mod renumber;
+#[cfg(feature = "serde")]
+mod serialization;
+mod table;
+mod validation;
+
+pub use allocation::{AllocationOptions, Policy};
+pub use diagram::{Depth, DiagramOptions};
+pub use diff::{Change, Diff};
+pub use document::{Document, Node, NodeKind};
+pub use error::{Error, Expected, ParseError};
+pub use merge::{Conflict, Merge};
+pub use number::{AreaNumber, CategoryNumber, IdNumber};
+pub use render::RenderOptions;
+#[cfg(feature = "serde")]
+pub use serialization::as_string;
+pub use validation::{Diagnostic, Severity, Validation};
 
 /// `10-19 Area`
 ///
@@ -26,8 +58,10 @@ use std::fmt;
 /// ```
 #[derive(Debug, Default, Eq, Clone)]
 pub struct Area {
-    area: String,
+    area: AreaNumber,
     name: String,
+    metadata: Metadata,
+    comments: Comments,
 }
 
 /// `11 Category`
@@ -54,9 +88,11 @@ pub struct Area {
 /// ```
 #[derive(Debug, Default, Eq, Clone)]
 pub struct Category {
-    area: String,
-    category: String,
+    area: AreaNumber,
+    category: CategoryNumber,
     name: String,
+    metadata: Metadata,
+    comments: Comments,
 }
 
 /// `11.01 Id`
@@ -84,10 +120,34 @@ pub struct Category {
 /// ```
 #[derive(Debug, Default, Eq, Clone)]
 pub struct Id {
-    area: String,
-    category: String,
-    id: String,
+    area: AreaNumber,
+    category: CategoryNumber,
+    id: IdNumber,
     name: String,
+    metadata: Metadata,
+    comments: Comments,
+}
+
+/// An `Area`, `Category`, or `Id`.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::{Entry, Id};
+///
+/// let entry = Entry::Id(Id::new("11.01 Id").expect("Invalid id"));
+///
+/// assert_eq!(entry.get_number(), "11.01");
+/// assert_eq!(entry.to_string(), "11.01 Id");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    /// `10-19 Area`
+    Area(Area),
+    /// `11 Category`
+    Category(Category),
+    /// `11.01 Id`
+    Id(Id),
 }
 
 /// `00.00 Index.txt`
@@ -109,25 +169,75 @@ pub struct Id {
 /// use johnnydecimal::Index;
 ///
 /// if let Ok(index) = Index::new("10-19 Area\n13 Category\n13.05 Id") {
-///     assert!(index.get_areas().iter().count() == 1);
-///     assert!(index.get_categories().iter().count() == 1);
-///     assert!(index.get_ids().iter().count() == 1);
+///     assert!(index.get_areas().count() == 1);
+///     assert!(index.get_categories().count() == 1);
+///     assert!(index.get_ids().count() == 1);
 /// } else {
 ///     panic!("Invalid index");
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Default, Clone)]
 pub struct Index {
-    areas: Vec<Area>,
-    categories: Vec<Category>,
-    ids: Vec<Id>,
+    areas: BTreeMap<AreaNumber, Area>,
+    categories: BTreeMap<CategoryNumber, Category>,
+    ids: BTreeMap<IdNumber, Id>,
+    trailing_comments: Vec<String>,
+}
+
+/// `- key: value`
+///
+/// `Metadata` is an ordered list of key/value pairs attached to an `Area`, `Category`, or `Id`.
+///
+/// In an `Index`, each pair is written on its own line after the entry it belongs to.
+///
+/// <https://github.com/johnnydecimal/jdcm.al__index-spec#metadata>
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::Index;
+///
+/// let index = Index::new("10-19 Area\n- owner: Alice\n11 Category").expect("Invalid index");
+///
+/// assert_eq!(index.get_area("10-19").map(|area| area.get_metadata().get("owner")), Some(Some("Alice")));
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Metadata {
+    pairs: Vec<(String, String)>,
+}
+
+/// `// Comment` or `/* Comment */`
+///
+/// `Comments` are the JavaScript comments attached to an `Area`, `Category`, or `Id`.
+///
+/// Comments on their own lines are attached to the entry below them. A comment at the end of
+/// an entry's line is attached to that entry as its inline comment, and a comment at the end of
+/// a metadata line is attached to the entry as the inline comment of that key.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::Index;
+///
+/// let index = Index::new("// Work\n10-19 Area // Inline\n- owner: Alice // Until May\n11 Category").expect("Invalid index");
+/// let comments = index.get_area("10-19").expect("Missing area").get_comments();
+///
+/// assert_eq!(comments.get_above(), &vec!["// Work".to_string()]);
+/// assert_eq!(comments.get_inline(), Some("// Inline"));
+/// assert_eq!(comments.get_metadata_inline("owner"), Some("// Until May"));
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Comments {
+    above: Vec<String>,
+    inline: Option<String>,
+    metadata: BTreeMap<String, String>,
 }
 
 impl Area {
     /// Creates a new `Area` from a given `&str`, returning a `Result`.
     ///
     /// If `Ok`, returns the `Area`.
-    /// If `Err`, returns a `&str` of the error message.
+    /// If `Err`, returns an `Error::Parse` with the column where parsing failed.
     ///
     /// # Example
     ///
@@ -141,52 +251,52 @@ impl Area {
     ///     panic!("Invalid area");
     /// }
     /// ```
-    pub fn new(str: &str) -> Result<Self, &'static str> {
+    pub fn new(str: &str) -> Result<Self, Error> {
         let chars: Vec<char> = str.chars().collect();
 
         if chars.len() < 5 {
-            return Err("Given area is too short to follow a0-a9");
+            return Err(parse_error(str, chars.len() + 1, Expected::Area, "Given area is too short to follow a0-a9"));
         }
 
         if !chars[0].is_ascii_digit() {
-            return Err("Given area does not have valid starting digit a in a0");
+            return Err(parse_error(str, 1, Expected::Area, "Given area does not have valid starting digit a in a0"));
         }
 
         if chars[1] != '0' {
-            return Err("Given area does not have a starting digit that ends in 0 in a0-a9");
+            return Err(parse_error(str, 2, Expected::Area, "Given area does not have a starting digit that ends in 0 in a0-a9"));
         }
 
         if chars[2] != '-' {
-            return Err("Given area does not have dash separator in a0-a9");
+            return Err(parse_error(str, 3, Expected::Area, "Given area does not have dash separator in a0-a9"));
         }
 
         if chars[3] != chars[0] {
-            return Err("Given area does not have an ending digit equal to a in a0-a9");
+            return Err(parse_error(str, 4, Expected::Area, "Given area does not have an ending digit equal to a in a0-a9"));
         }
 
         if chars[4] != '9' {
-            return Err("Given area does not end with 9 in a0-a9");
+            return Err(parse_error(str, 5, Expected::Area, "Given area does not end with 9 in a0-a9"));
         }
 
         if chars.len() < 7 {
-            return Err("Given area is too short to have a name");
+            return Err(parse_error(str, chars.len() + 1, Expected::Area, "Given area is too short to have a name"));
         }
 
         if chars[5] != ' ' {
-            return Err("Given area does not have a space separator");
+            return Err(parse_error(str, 6, Expected::Area, "Given area does not have a space separator"));
         }
 
-        let area = chars[0..5].iter().collect::<String>();
+        let area = AreaNumber::from_digit(chars[0]);
         let name = chars[6..chars.len()].iter().collect::<String>();
 
         if sanitise(&name) != name {
-            return Err("Given area does not have a valid title for file names");
+            return Err(parse_error(str, 7, Expected::Area, "Given area does not have a valid title for file names"));
         }
 
-        Ok(Self { area, name })
+        Ok(Self { area, name, ..Default::default() })
     }
 
-    /// Area `10-19`: The string `a0-a9` derived from `a0-a9 <title>`.
+    /// Area `10-19`: The `AreaNumber` `a0-a9` derived from `a0-a9 <title>`.
     ///
     /// # Example
     ///
@@ -197,8 +307,8 @@ impl Area {
     ///
     /// assert!(area.get_area() == "30-39");
     /// ```
-    pub fn get_area(&self) -> &str {
-        &self.area
+    pub fn get_area(&self) -> AreaNumber {
+        self.area
     }
 
     /// Title `My Title`: The string `<title>` derived from `a0-a9 <title>`
@@ -217,15 +327,44 @@ impl Area {
     }
 
     /// Change the `Title` of an existing `a0-a9 <title>`.
-    pub fn set_name(&mut self, name: &str) -> Result<&Self, &'static str> {
+    pub fn set_name(&mut self, name: &str) -> Result<&Self, Error> {
         if sanitise(name) != name {
-            return Err("Invalid file name");
+            return Err(Error::InvalidTitle(name.to_string()));
         }
 
         self.name = name.to_string();
 
         Ok(self)
     }
+
+    /// Metadata `- key: value`: The key/value pairs listed after `a0-a9 <title>`.
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Set the `value` of a given metadata `key`, replacing any existing value.
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> Result<&Self, Error> {
+        self.metadata.set(key, value)?;
+
+        Ok(self)
+    }
+
+    /// Remove a given metadata `key`, returning its value if it existed.
+    pub fn remove_metadata(&mut self, key: &str) -> Option<String> {
+        self.metadata.remove(key)
+    }
+
+    /// Comments `// Comment`: The comments above and at the end of `a0-a9 <title>`.
+    pub fn get_comments(&self) -> &Comments {
+        &self.comments
+    }
+
+    /// Replace the `Comments` of an existing `a0-a9 <title>`.
+    pub fn set_comments(&mut self, comments: Comments) -> &Self {
+        self.comments = comments;
+
+        self
+    }
 }
 
 impl PartialEq for Area {
@@ -246,6 +385,14 @@ impl PartialOrd for Area {
     }
 }
 
+impl FromStr for Area {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self, Error> {
+        Self::new(str)
+    }
+}
+
 impl fmt::Display for Area {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} {}", self.area, self.name)
@@ -256,7 +403,7 @@ impl Category {
     /// Creates a new `Category` from a given `&str`, returning a `Result`.
     ///
     /// If `Ok`, returns the `Category`.
-    /// If `Err`, returns a `&str` of the error message.
+    /// If `Err`, returns an `Error::Parse` with the column where parsing failed.
     ///
     /// # Example
     ///
@@ -271,37 +418,47 @@ impl Category {
     ///     panic!("Invalid category");
     /// }
     /// ```
-    pub fn new(str: &str) -> Result<Self, &'static str> {
+    pub fn new(str: &str) -> Result<Self, Error> {
         let chars: Vec<char> = str.chars().collect();
 
         if chars.len() < 2 {
-            return Err("Given category is too short to follow [00-99]");
+            return Err(parse_error(str, chars.len() + 1, Expected::Category, "Given category is too short to follow [00-99]"));
         }
 
         if !chars[0].is_ascii_digit() || !chars[1].is_ascii_digit() {
-            return Err("Given category does not have valid starting digits [00-99]");
+            return Err(parse_error(
+                str,
+                if chars[0].is_ascii_digit() { 2 } else { 1 },
+                Expected::Category,
+                "Given category does not have valid starting digits [00-99]",
+            ));
         }
 
         if chars.len() < 4 {
-            return Err("Given category is too short to have a name");
+            return Err(parse_error(str, chars.len() + 1, Expected::Category, "Given category is too short to have a name"));
         }
 
         if chars[2] != ' ' {
-            return Err("Given category does not have a space separator");
+            return Err(parse_error(str, 3, Expected::Category, "Given category does not have a space separator"));
         }
 
-        let category = chars[0..2].iter().collect::<String>();
-        let area = [chars[0], '0', '-', chars[0], '9'].iter().collect::<String>();
+        let category = CategoryNumber::from_digits(chars[0], chars[1]);
+        let area = category.get_area();
         let name = chars[3..chars.len()].iter().collect::<String>();
 
         if sanitise(&name) != name {
-            return Err("Given category does not have a valid title for file names");
+            return Err(parse_error(str, 4, Expected::Category, "Given category does not have a valid title for file names"));
         }
 
-        Ok(Self { category, area, name })
+        Ok(Self {
+            category,
+            area,
+            name,
+            ..Default::default()
+        })
     }
 
-    /// Area `10-19`: The string `a0-a9` derived from `ac <title>`.
+    /// Area `10-19`: The `AreaNumber` `a0-a9` derived from `ac <title>`.
     ///
     /// # Example
     ///
@@ -312,11 +469,11 @@ impl Category {
     ///
     /// assert!(category.get_area() == "50-59");
     /// ```
-    pub fn get_area(&self) -> &str {
-        &self.area
+    pub fn get_area(&self) -> AreaNumber {
+        self.area
     }
 
-    /// Category `11`: The string `ac` derived from `ac <title>`.
+    /// Category `11`: The `CategoryNumber` `ac` derived from `ac <title>`.
     ///
     /// # Example
     ///
@@ -327,8 +484,8 @@ impl Category {
     ///
     /// assert!(category.get_category() == "53");
     /// ```
-    pub fn get_category(&self) -> &str {
-        &self.category
+    pub fn get_category(&self) -> CategoryNumber {
+        self.category
     }
 
     /// Title `My Title`: The string `<title>` derived from `ac <title>`.
@@ -347,15 +504,44 @@ impl Category {
     }
 
     /// Change the `Title` of an existing `ac <title>`.
-    pub fn set_name(&mut self, name: &str) -> Result<&Self, &'static str> {
+    pub fn set_name(&mut self, name: &str) -> Result<&Self, Error> {
         if sanitise(name) != name {
-            return Err("Invalid file name");
+            return Err(Error::InvalidTitle(name.to_string()));
         }
 
         self.name = name.to_string();
 
         Ok(self)
     }
+
+    /// Metadata `- key: value`: The key/value pairs listed after `ac <title>`.
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Set the `value` of a given metadata `key`, replacing any existing value.
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> Result<&Self, Error> {
+        self.metadata.set(key, value)?;
+
+        Ok(self)
+    }
+
+    /// Remove a given metadata `key`, returning its value if it existed.
+    pub fn remove_metadata(&mut self, key: &str) -> Option<String> {
+        self.metadata.remove(key)
+    }
+
+    /// Comments `// Comment`: The comments above and at the end of `ac <title>`.
+    pub fn get_comments(&self) -> &Comments {
+        &self.comments
+    }
+
+    /// Replace the `Comments` of an existing `ac <title>`.
+    pub fn set_comments(&mut self, comments: Comments) -> &Self {
+        self.comments = comments;
+
+        self
+    }
 }
 
 impl PartialEq for Category {
@@ -376,6 +562,14 @@ impl PartialOrd for Category {
     }
 }
 
+impl FromStr for Category {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self, Error> {
+        Self::new(str)
+    }
+}
+
 impl fmt::Display for Category {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} {}", self.category, self.name)
@@ -386,7 +580,7 @@ impl Id {
     /// Creates a new `Id` from a given `&str`, returning a `Result`.
     ///
     /// If `Ok`, returns the `Id`.
-    /// If `Err`, returns a `&str` of the error message.
+    /// If `Err`, returns an `Error::Parse` with the column where parsing failed.
     ///
     /// # Example
     ///
@@ -402,50 +596,61 @@ impl Id {
     ///     panic!("Invalid id");
     /// }
     /// ```
-    pub fn new(str: &str) -> Result<Self, &'static str> {
+    pub fn new(str: &str) -> Result<Self, Error> {
         let chars: Vec<char> = str.chars().collect();
 
         if chars.len() < 5 {
-            return Err("Given id is too short to follow ac.id");
+            return Err(parse_error(str, chars.len() + 1, Expected::Id, "Given id is too short to follow ac.id"));
         }
 
         if !chars[0].is_ascii_digit() {
-            return Err("Given id does not have a valid starting area digit a in ac.id");
+            return Err(parse_error(str, 1, Expected::Id, "Given id does not have a valid starting area digit a in ac.id"));
         }
 
         if !chars[1].is_ascii_digit() {
-            return Err("Given id does not have a valid category ac in ac.id");
+            return Err(parse_error(str, 2, Expected::Id, "Given id does not have a valid category ac in ac.id"));
         }
 
         if chars[2] != '.' {
-            return Err("Given id does not have a decimal separator in ac.id");
+            return Err(parse_error(str, 3, Expected::Id, "Given id does not have a decimal separator in ac.id"));
         }
 
         if !chars[3].is_ascii_digit() || !chars[4].is_ascii_digit() {
-            return Err("Given id does not have valid digits id to follow ac.id");
+            return Err(parse_error(
+                str,
+                if chars[3].is_ascii_digit() { 5 } else { 4 },
+                Expected::Id,
+                "Given id does not have valid digits id to follow ac.id",
+            ));
         }
 
         if chars.len() < 7 {
-            return Err("Given id is too short to have a name");
+            return Err(parse_error(str, chars.len() + 1, Expected::Id, "Given id is too short to have a name"));
         }
 
         if chars[5] != ' ' {
-            return Err("Given id does not have a space separator");
+            return Err(parse_error(str, 6, Expected::Id, "Given id does not have a space separator"));
         }
 
-        let id = chars[0..5].iter().collect::<String>();
-        let category = chars[0..2].iter().collect::<String>();
-        let area = [chars[0], '0', '-', chars[0], '9'].iter().collect::<String>();
+        let category = CategoryNumber::from_digits(chars[0], chars[1]);
+        let id = IdNumber::from_digits(category, chars[3], chars[4]);
+        let area = category.get_area();
         let name = chars[6..chars.len()].iter().collect::<String>();
 
         if sanitise(&name) != name {
-            return Err("Given id does not have a valid title for file names");
+            return Err(parse_error(str, 7, Expected::Id, "Given id does not have a valid title for file names"));
         }
 
-        Ok(Self { id, category, area, name })
+        Ok(Self {
+            id,
+            category,
+            area,
+            name,
+            ..Default::default()
+        })
     }
 
-    /// Area `10-19`: The string `a0-a9` derived from `ac.id <title>`.
+    /// Area `10-19`: The `AreaNumber` `a0-a9` derived from `ac.id <title>`.
     ///
     /// # Example
     ///
@@ -456,11 +661,11 @@ impl Id {
     ///
     /// assert!(id.get_area() == "30-39");
     /// ```
-    pub fn get_area(&self) -> &str {
-        &self.area
+    pub fn get_area(&self) -> AreaNumber {
+        self.area
     }
 
-    /// Category `11`: The string `ac` derived from `ac.id <title>`.
+    /// Category `11`: The `CategoryNumber` `ac` derived from `ac.id <title>`.
     ///
     /// # Example
     ///
@@ -471,11 +676,11 @@ impl Id {
     ///
     /// assert!(id.get_category() == "39");
     /// ```
-    pub fn get_category(&self) -> &str {
-        &self.category
+    pub fn get_category(&self) -> CategoryNumber {
+        self.category
     }
 
-    /// Id `11.01`: The string `ac.id` derived from `ac.id <title>`.
+    /// Id `11.01`: The `IdNumber` `ac.id` derived from `ac.id <title>`.
     ///
     /// # Example
     ///
@@ -486,8 +691,8 @@ impl Id {
     ///
     /// assert!(id.get_id() == "39.12");
     /// ```
-    pub fn get_id(&self) -> &str {
-        &self.id
+    pub fn get_id(&self) -> IdNumber {
+        self.id
     }
 
     /// Title `My Title`: The string `<title>` derived from `ac.id <title>`.
@@ -506,15 +711,44 @@ impl Id {
     }
 
     /// Change the `Title` of an existing `ac.id <title>`.
-    pub fn set_name(&mut self, name: &str) -> Result<&Self, &'static str> {
+    pub fn set_name(&mut self, name: &str) -> Result<&Self, Error> {
         if sanitise(name) != name {
-            return Err("Invalid file name");
+            return Err(Error::InvalidTitle(name.to_string()));
         }
 
         self.name = name.to_string();
 
         Ok(self)
     }
+
+    /// Metadata `- key: value`: The key/value pairs listed after `ac.id <title>`.
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Set the `value` of a given metadata `key`, replacing any existing value.
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> Result<&Self, Error> {
+        self.metadata.set(key, value)?;
+
+        Ok(self)
+    }
+
+    /// Remove a given metadata `key`, returning its value if it existed.
+    pub fn remove_metadata(&mut self, key: &str) -> Option<String> {
+        self.metadata.remove(key)
+    }
+
+    /// Comments `// Comment`: The comments above and at the end of `ac.id <title>`.
+    pub fn get_comments(&self) -> &Comments {
+        &self.comments
+    }
+
+    /// Replace the `Comments` of an existing `ac.id <title>`.
+    pub fn set_comments(&mut self, comments: Comments) -> &Self {
+        self.comments = comments;
+
+        self
+    }
 }
 
 impl PartialEq for Id {
@@ -535,171 +769,466 @@ impl PartialOrd for Id {
     }
 }
 
+impl FromStr for Id {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self, Error> {
+        Self::new(str)
+    }
+}
+
 impl fmt::Display for Id {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{} {}", self.id, self.name)
     }
 }
 
-impl Index {
-    /// Creates a new `Index` from a given `&str`, returning a `Result`.
+impl Entry {
+    /// Returns the number of the entry, like `10-19`, `11`, or `11.01`.
+    pub fn get_number(&self) -> String {
+        match self {
+            Self::Area(area) => area.area.to_string(),
+            Self::Category(category) => category.category.to_string(),
+            Self::Id(id) => id.id.to_string(),
+        }
+    }
+
+    /// Returns the title of the entry.
+    pub fn get_name(&self) -> &str {
+        match self {
+            Self::Area(area) => &area.name,
+            Self::Category(category) => &category.name,
+            Self::Id(id) => &id.name,
+        }
+    }
+
+    /// Returns true if the entry is only a number like `11.01`, parsed as a reference to an
+    /// existing entry rather than a new one.
+    pub fn is_reference(&self) -> bool {
+        self.get_name().is_empty()
+    }
+
+    /// Returns a key that sorts entries in the order of an `Index`.
+    pub(crate) fn key(&self) -> (AreaNumber, Option<CategoryNumber>, Option<IdNumber>) {
+        match self {
+            Self::Area(area) => (area.area, None, None),
+            Self::Category(category) => (category.area, Some(category.category), None),
+            Self::Id(id) => (id.area, Some(id.category), Some(id.id)),
+        }
+    }
+}
+
+impl FromStr for Entry {
+    type Err = Error;
+
+    /// Parses an `Area`, `Category`, or `Id` from its line in an index, detecting the kind.
     ///
-    /// If `Ok`, returns the `Index`.
-    /// If `Err`, returns a `&str` of the error message.
+    /// A bare number like `10-19`, `11`, or `11.01` is parsed as a reference without a title.
+    /// If no kind matches, the error explains why for the kind the `&str` most likely tried to be.
     ///
     /// # Example
     ///
     /// ```
-    /// use johnnydecimal::Index;
+    /// use johnnydecimal::Entry;
     ///
-    /// if let Ok(index) = Index::new("10-19 Area\n11 Category\n11.01 Id") {
-    ///     assert!(index.get_areas().iter().count() == 1);
-    ///     assert!(index.get_categories().iter().count() == 1);
-    ///     assert!(index.get_ids().iter().count() == 1);
-    /// } else {
-    ///     panic!("Invalid index");
-    /// }
+    /// let entry: Entry = "11.01 My Id".parse().expect("Invalid entry");
+    ///
+    /// assert!(matches!(entry, Entry::Id(_)));
+    /// assert!(!entry.is_reference());
+    ///
+    /// let reference: Entry = "10-19".parse().expect("Invalid entry");
+    ///
+    /// assert!(matches!(reference, Entry::Area(_)));
+    /// assert!(reference.is_reference());
+    ///
+    /// assert!("11.1 Typo".parse::<Entry>().is_err());
     /// ```
+    fn from_str(str: &str) -> Result<Self, Error> {
+        let str = str.trim();
+
+        if let Ok(id) = str.parse::<IdNumber>() {
+            return Ok(Self::Id(Id {
+                area: id.get_area(),
+                category: id.get_category(),
+                id,
+                ..Default::default()
+            }));
+        }
+
+        if let Ok(category) = str.parse::<CategoryNumber>() {
+            return Ok(Self::Category(Category {
+                area: category.get_area(),
+                category,
+                ..Default::default()
+            }));
+        }
+
+        if let Ok(area) = str.parse::<AreaNumber>() {
+            return Ok(Self::Area(Area { area, ..Default::default() }));
+        }
+
+        Id::new(str)
+            .map(Self::Id)
+            .or_else(|_| Category::new(str).map(Self::Category))
+            .or_else(|_| Area::new(str).map(Self::Area))
+            .map_err(|_| entry_error(str))
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_reference() {
+            return write!(f, "{}", self.get_number());
+        }
+
+        match self {
+            Self::Area(area) => write!(f, "{area}"),
+            Self::Category(category) => write!(f, "{category}"),
+            Self::Id(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+impl Metadata {
+    /// Returns the value of a given `key`, if it exists.
     ///
-    /// # Panics
+    /// # Example
     ///
-    /// Key/value pairs and comments have not been implemented.
-    pub fn new(str: &str) -> Result<Self, &str> {
-        let mut areas: Vec<Area> = vec![];
-        let mut categories: Vec<Category> = vec![];
-        let mut ids: Vec<Id> = vec![];
+    /// ```
+    /// use johnnydecimal::Id;
+    ///
+    /// let mut id = Id::new("11.01 My Id").expect("Invalid id");
+    ///
+    /// id.set_metadata("location", "Desk drawer").expect("Invalid metadata");
+    ///
+    /// assert_eq!(id.get_metadata().get("location"), Some("Desk drawer"));
+    /// assert_eq!(id.get_metadata().get("owner"), None);
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
 
-        for line in str.lines() {
-            let line = line.trim_start_matches(' ');
+    /// Sets the `value` of a given `key`.
+    ///
+    /// Existing keys keep their position. New keys are appended to the end. Keys and values
+    /// can't contain `//` or `/*` at their start or after white space, which would be read as a
+    /// comment.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<&Self, Error> {
+        if key.is_empty() || key.trim() != key {
+            return Err(Error::InvalidMetadata("Given metadata key must not be empty or surrounded by white space"));
+        }
 
-            if line.is_empty() {
-                continue;
-            }
+        if key.contains(':') || key.contains('\n') {
+            return Err(Error::InvalidMetadata("Given metadata key must not contain a colon or line break"));
+        }
 
-            if line.starts_with('-') {
-                todo!("Add support for key/value pairs");
-            }
+        if value.contains('\n') {
+            return Err(Error::InvalidMetadata("Given metadata value must not contain a line break"));
+        }
 
-            if line.contains("//") {
-                todo!("Add support for comments");
-            }
+        if lexer::find_comment(key).is_some() || lexer::find_comment(value).is_some() {
+            return Err(Error::InvalidMetadata("Given metadata must not contain a comment like // or /*"));
+        }
 
-            if line.contains("/*") {
-                todo!("Add support for multi-line comments");
-            }
+        if let Some(pair) = self.pairs.iter_mut().find(|(k, _)| k == key) {
+            pair.1 = value.to_string();
+        } else {
+            self.pairs.push((key.to_string(), value.to_string()));
+        }
 
-            if let Ok(id) = Id::new(line) {
-                if ids.contains(&id) {
-                    return Err("Duplicate ids are not allowed");
-                }
+        Ok(self)
+    }
 
-                if !categories.iter().any(|c| c.category == id.category) {
-                    return Err("A given id has no associated category");
-                }
+    /// Removes a given `key`, returning its value if it existed.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let position = self.pairs.iter().position(|(k, _)| k == key)?;
 
-                ids.push(id);
+        Some(self.pairs.remove(position).1)
+    }
 
-                continue;
-            }
+    /// Returns an iterator over the `(key, value)` pairs in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
 
-            if let Ok(category) = Category::new(line) {
-                if categories.contains(&category) {
-                    return Err("Duplicate categories are not allowed");
-                }
+    /// Returns the number of key/value pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
 
-                if !areas.iter().any(|a| a.area == category.area) {
-                    return Err("A given category has no associated area");
-                }
+    /// Returns `true` if there are no key/value pairs.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
 
-                categories.push(category);
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self
+            .iter()
+            .map(|(key, value)| if value.is_empty() { format!("- {key}:") } else { format!("- {key}: {value}") })
+            .collect();
 
-                continue;
-            }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
 
-            if let Ok(area) = Area::new(line) {
-                if areas.contains(&area) {
-                    return Err("Duplicate areas are not allowed");
-                }
+impl Comments {
+    /// Comments on their own lines directly above an entry, in order.
+    pub fn get_above(&self) -> &Vec<String> {
+        &self.above
+    }
 
-                areas.push(area);
+    /// The comment at the end of an entry's line, if any.
+    pub fn get_inline(&self) -> Option<&str> {
+        self.inline.as_deref()
+    }
 
-                continue;
+    /// Adds a comment to be written on its own line above an entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::Comments;
+    ///
+    /// let mut comments = Comments::default();
+    ///
+    /// assert!(comments.add_above("// Line comment").is_ok());
+    /// assert!(comments.add_above("/* Block\ncomment */").is_ok());
+    /// assert!(comments.add_above("Not a comment").is_err());
+    /// ```
+    pub fn add_above(&mut self, comment: &str) -> Result<&Self, Error> {
+        validate_comment(comment)?;
+
+        self.above.push(comment.to_string());
+
+        Ok(self)
+    }
+
+    /// Sets or clears the comment written at the end of an entry's line.
+    pub fn set_inline(&mut self, comment: Option<&str>) -> Result<&Self, Error> {
+        if let Some(comment) = comment {
+            validate_comment(comment)?;
+        }
+
+        self.inline = comment.map(str::to_string);
+
+        Ok(self)
+    }
+
+    /// The comment at the end of the line of a given metadata key, if any.
+    pub fn get_metadata_inline(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Sets or clears the comment written at the end of the line of a given metadata key.
+    ///
+    /// The comment is only written while the entry has metadata with that key.
+    pub fn set_metadata_inline(&mut self, key: &str, comment: Option<&str>) -> Result<&Self, Error> {
+        match comment {
+            Some(comment) => {
+                validate_comment(comment)?;
+
+                self.metadata.insert(key.to_string(), comment.to_string());
+            }
+            None => {
+                self.metadata.remove(key);
             }
+        }
+
+        Ok(self)
+    }
 
-            return Err("Given value was neither an Area, Category, or Id");
+    /// Returns `true` if there are no comments.
+    pub fn is_empty(&self) -> bool {
+        self.above.is_empty() && self.inline.is_none() && self.metadata.is_empty()
+    }
+}
+
+/// Checks that a given `&str` is exactly one `// line` or `/* block */` comment.
+fn validate_comment(comment: &str) -> Result<(), Error> {
+    if let Some(text) = comment.strip_prefix("//") {
+        if text.contains('\n') {
+            return Err(Error::InvalidComment("Given line comment must not contain a line break"));
         }
 
-        areas.sort_unstable();
-        categories.sort_unstable();
-        ids.sort_unstable();
+        return Ok(());
+    }
 
-        Ok(Self { areas, categories, ids })
+    if let Some(text) = comment.strip_prefix("/*").and_then(|text| text.strip_suffix("*/")) {
+        if text.contains("*/") {
+            return Err(Error::InvalidComment("Given block comment must not contain */ before its end"));
+        }
+
+        return Ok(());
     }
 
-    /// Create an Index from vectors
+    Err(Error::InvalidComment("Given comment does not start with // or /* and end with */"))
+}
+
+/// Creates an `Error::Parse` on line 1 of a given `&str`.
+fn parse_error(str: &str, column: usize, expected: Expected, reason: &'static str) -> Error {
+    Error::Parse(ParseError::new(str, column, expected, reason))
+}
+
+/// Returns the error of the kind of entry that a given `&str` most likely tried to be.
+///
+/// `ac.id` is assumed if the third character is `.`, `a0-a9` if it's `-`, and `ac` if the first
+/// two characters are digits.
+fn entry_error(str: &str) -> Error {
+    let chars: Vec<char> = str.chars().take(3).collect();
+
+    let error = match chars.as_slice() {
+        [_, _, '.'] => Id::new(str).err(),
+        [_, _, '-'] => Area::new(str).err(),
+        [a, b, ..] if a.is_ascii_digit() && b.is_ascii_digit() => Category::new(str).err(),
+        _ => None,
+    };
+
+    error.unwrap_or_else(|| {
+        parse_error(
+            str,
+            1,
+            Expected::Entry,
+            "Given value does not start with two digits, so it is neither an area a0-a9, a category ac, nor an id ac.id",
+        )
+    })
+}
+
+/// Parses a `- key: value` line into its key and value.
+fn parse_metadata(line: &str) -> Result<(&str, &str), ParseError> {
+    let Some(pair) = line.strip_prefix('-') else {
+        return Err(ParseError::new(line, 1, Expected::Metadata, "Given metadata does not start with a dash in - key: value"));
+    };
+
+    let Some((key, value)) = pair.split_once(':') else {
+        return Err(ParseError::new(
+            line,
+            line.chars().count() + 1,
+            Expected::Metadata,
+            "Given metadata does not have a colon separator in - key: value",
+        ));
+    };
+
+    let key = key.trim();
+
+    if key.is_empty() {
+        return Err(ParseError::new(line, 2, Expected::Metadata, "Given metadata does not have a key in - key: value"));
+    }
+
+    Ok((key, value.trim()))
+}
+
+impl Index {
+    /// Creates a new `Index` from a given `&str`, returning a `Result`.
     ///
-    /// Note that checking for duplicates and sorting the vectors (although possibly redundant)
-    /// removes the risk of such vectors being unverified and producing an undefined state.
-    pub fn with_vecs(areas: &Vec<Area>, categories: &Vec<Category>, ids: &Vec<Id>) -> Result<Self, &'static str> {
-        if has_duplicate(areas) || has_duplicate(categories) || has_duplicate(ids) {
-            return Err("Duplicates exist.");
+    /// If `Ok`, returns the `Index`.
+    /// If `Err`, returns an `Error` with the line where parsing or validation failed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::Index;
+    ///
+    /// if let Ok(index) = Index::new("10-19 Area\n11 Category\n11.01 Id") {
+    ///     assert!(index.get_areas().count() == 1);
+    ///     assert!(index.get_categories().count() == 1);
+    ///     assert!(index.get_ids().count() == 1);
+    /// } else {
+    ///     panic!("Invalid index");
+    /// }
+    /// ```
+    ///
+    /// Key/value metadata lines (`- key: value`) are attached to the area, category, or id
+    /// directly before them.
+    ///
+    /// Comments (`// Comment` and `/* Comment */`) are attached to the nearest entry. See `Comments`.
+    ///
+    /// Only the first problem is returned. Use `Index::validate` to get every problem.
+    pub fn new(str: &str) -> Result<Self, Error> {
+        let (index, diagnostics) = validation::parse(str);
+
+        match diagnostics.into_iter().find(|diagnostic| diagnostic.get_severity() == Severity::Error) {
+            Some(diagnostic) => Err(diagnostic.into_error()),
+            None => Ok(index),
         }
+    }
 
-        let mut areas = areas.to_owned();
-        let mut categories = categories.to_owned();
-        let mut ids = ids.to_owned();
+    /// Create an Index from vectors
+    ///
+    /// Note that checking for duplicates and orphans removes the risk of such vectors being
+    /// unverified and producing an undefined state. The order of the vectors doesn't matter.
+    pub fn with_vecs(areas: &[Area], categories: &[Category], ids: &[Id]) -> Result<Self, Error> {
+        let number = find_duplicate(areas.iter().map(|area| area.area.to_string()))
+            .or_else(|| find_duplicate(categories.iter().map(|category| category.category.to_string())))
+            .or_else(|| find_duplicate(ids.iter().map(|id| id.id.to_string())));
+
+        if let Some(number) = number {
+            return Err(duplicate(number));
+        }
 
-        areas.sort_unstable();
-        categories.sort_unstable();
-        ids.sort_unstable();
+        let index = Self {
+            areas: areas.iter().map(|area| (area.area, area.clone())).collect(),
+            categories: categories.iter().map(|category| (category.category, category.clone())).collect(),
+            ids: ids.iter().map(|id| (id.id, id.clone())).collect(),
+            trailing_comments: vec![],
+        };
 
-        for id in &ids {
-            if !categories.iter().any(|c| c.category == id.category) {
-                return Err("A given id has no associated category");
+        for id in index.ids.values() {
+            if !index.categories.contains_key(&id.category) {
+                return Err(orphan(id.id, id.category));
             }
         }
 
-        for category in &categories {
-            if !areas.iter().any(|a| a.area == category.area) {
-                return Err("A given category has no associated area");
+        for category in index.categories.values() {
+            if !index.areas.contains_key(&category.area) {
+                return Err(orphan(category.category, category.area));
             }
         }
 
-        Ok(Self { areas, categories, ids })
+        Ok(index)
     }
 
     /// Area `10-19`: The string `a0-a9` derived from `ac.id <title>`.
-    pub fn get_areas(&self) -> &Vec<Area> {
-        &self.areas
+    ///
+    /// Returns every area in order.
+    pub fn get_areas(&self) -> impl ExactSizeIterator<Item = &Area> + DoubleEndedIterator {
+        self.areas.values()
     }
 
-    /// Returns a `Result` of the `Area` of a given `Category`.
-    pub fn get_area_from_category(&self, category: &Category) -> Result<&Area, &'static str> {
-        for area in &self.areas {
-            if area.area == category.area {
-                return Ok(area);
-            }
-        }
+    /// Returns the `Area` with a given number like `10-19`, if it exists.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::Index;
+    ///
+    /// let index = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("Invalid index");
+    ///
+    /// assert_eq!(index.get_area("10-19").map(|area| area.get_name()), Some("Area"));
+    /// assert_eq!(index.get_category("11").map(|category| category.get_name()), Some("Category"));
+    /// assert_eq!(index.get_id("11.01").map(|id| id.get_name()), Some("Id"));
+    /// assert!(index.get_id("11.02").is_none());
+    /// ```
+    pub fn get_area<N: TryInto<AreaNumber>>(&self, number: N) -> Option<&Area> {
+        self.areas.get(&number.try_into().ok()?)
+    }
 
-        Err("The given category does not have a parent area in the index.")
+    /// Returns a `Result` of the `Area` of a given `Category`.
+    pub fn get_area_from_category(&self, category: &Category) -> Result<&Area, Error> {
+        self.areas.get(&category.area).ok_or_else(|| orphan(category.category, category.area))
     }
 
     /// Returns a `Result` of the `Area` of a given `Id`.
-    pub fn get_area_from_id(&self, id: &Id) -> Result<&Area, &'static str> {
-        for area in &self.areas {
-            if area.area == id.area {
-                return Ok(area);
-            }
-        }
-
-        Err("The given id does not have a parent area in the index.")
+    pub fn get_area_from_id(&self, id: &Id) -> Result<&Area, Error> {
+        self.areas.get(&id.area).ok_or_else(|| orphan(id.id, id.area))
     }
 
     /// Returns a `Result` with a `String` of the path for an `Area`.
     ///
     /// Note that `Area` does not need to be in `Index`, hence this
     /// function returns a *derived* path *for* an area.
-    pub fn derive_path_for_area(&self, area: &Area) -> Result<String, &'static str> {
+    pub fn derive_path_for_area(&self, area: &Area) -> Result<String, Error> {
         Ok(format!("/{}", area))
     }
 
@@ -707,7 +1236,7 @@ impl Index {
     ///
     /// Note that `Category` does not need to be in `Index`, hence this
     /// function returns a *derived* path *for* a category.
-    pub fn derive_path_for_category(&self, category: &Category) -> Result<String, &'static str> {
+    pub fn derive_path_for_category(&self, category: &Category) -> Result<String, Error> {
         let area = self.get_area_from_category(category)?;
 
         Ok(format!("/{}/{}", area, category))
@@ -717,7 +1246,7 @@ impl Index {
     ///
     /// Note that `Id` does not need to be in `Index`, hence this
     /// function returns a *derived* path *for* an id.
-    pub fn derive_path_for_id(&self, id: &Id) -> Result<String, &'static str> {
+    pub fn derive_path_for_id(&self, id: &Id) -> Result<String, Error> {
         let area = self.get_area_from_id(id)?;
         let category = self.get_category_from_id(id)?;
 
@@ -725,156 +1254,269 @@ impl Index {
     }
 
     /// Category `11`: The string `ac` derived from `ac.id <title>`.
-    pub fn get_categories(&self) -> &Vec<Category> {
-        &self.categories
+    ///
+    /// Returns every category in order.
+    pub fn get_categories(&self) -> impl ExactSizeIterator<Item = &Category> + DoubleEndedIterator {
+        self.categories.values()
     }
 
-    /// Returns a `Result` of the `Category` of a given `Id`.
-    pub fn get_category_from_id(&self, id: &Id) -> Result<&Category, &'static str> {
-        for category in &self.categories {
-            if category.category == id.category {
-                return Ok(category);
-            }
-        }
+    /// Returns the `Category` with a given number like `11`, if it exists.
+    pub fn get_category<N: TryInto<CategoryNumber>>(&self, number: N) -> Option<&Category> {
+        self.categories.get(&number.try_into().ok()?)
+    }
 
-        Err("The given id does not have a parent category in the index.")
+    /// Returns a `Result` of the `Category` of a given `Id`.
+    pub fn get_category_from_id(&self, id: &Id) -> Result<&Category, Error> {
+        self.categories.get(&id.category).ok_or_else(|| orphan(id.id, id.category))
     }
 
     /// Id `11.01`: The string `ac.id` derived from `ac.id <title>`.
-    pub fn get_ids(&self) -> &Vec<Id> {
-        &self.ids
+    ///
+    /// Returns every id in order.
+    pub fn get_ids(&self) -> impl ExactSizeIterator<Item = &Id> + DoubleEndedIterator {
+        self.ids.values()
+    }
+
+    /// Returns the `Id` with a given number like `11.01`, if it exists.
+    pub fn get_id<N: TryInto<IdNumber>>(&self, number: N) -> Option<&Id> {
+        self.ids.get(&number.try_into().ok()?)
+    }
+
+    /// Returns the entry of the `Index` with the same number as a given `Entry`, if it exists.
+    ///
+    /// This resolves a reference like `11.01` to the entry with its title.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::{Entry, Index};
+    ///
+    /// let index = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("Invalid index");
+    /// let reference: Entry = "11.01".parse().expect("Invalid entry");
+    ///
+    /// assert_eq!(index.get_entry(&reference).map(|entry| entry.to_string()), Some("11.01 Id".to_string()));
+    /// ```
+    pub fn get_entry(&self, entry: &Entry) -> Option<Entry> {
+        match entry {
+            Entry::Area(area) => self.areas.get(&area.area).cloned().map(Entry::Area),
+            Entry::Category(category) => self.categories.get(&category.category).cloned().map(Entry::Category),
+            Entry::Id(id) => self.ids.get(&id.id).cloned().map(Entry::Id),
+        }
+    }
+
+    /// Comments after the last entry of the index, in order.
+    pub fn get_trailing_comments(&self) -> &Vec<String> {
+        &self.trailing_comments
     }
 
-    /// Adds a valid `Area` to the vector of areas if there are no duplicates.
+    /// Adds a valid `Area` to the areas if there are no duplicates.
     ///
-    /// If successful, the area list is returned as `Ok`. Otherwise `Err`.
-    pub fn add_area(&mut self, area: &Area) -> Result<&Vec<Area>, &'static str> {
-        if self.areas.contains(area) {
-            return Err("Area already exists.");
+    /// If successful, the areas are returned as `Ok`. Otherwise `Err`.
+    pub fn add_area(&mut self, area: &Area) -> Result<Vec<&Area>, Error> {
+        if self.areas.contains_key(&area.area) {
+            return Err(duplicate(area.area));
         }
 
-        self.areas.push(area.clone());
-        self.areas.sort_unstable();
+        self.areas.insert(area.area, area.clone());
 
-        Ok(&self.areas)
+        Ok(self.get_areas().collect())
     }
 
-    /// Adds a valid `Category` to the vector of ids if there are no duplicates.
+    /// Adds a valid `Category` to the categories if there are no duplicates.
     ///
-    /// If successful, the category list is returned as `Ok`. Otherwise `Err`.
-    pub fn add_category(&mut self, category: &Category) -> Result<&Vec<Category>, &'static str> {
-        if self.categories.contains(category) {
-            return Err("Category already exists.");
+    /// If successful, the categories are returned as `Ok`. Otherwise `Err`.
+    pub fn add_category(&mut self, category: &Category) -> Result<Vec<&Category>, Error> {
+        if self.categories.contains_key(&category.category) {
+            return Err(duplicate(category.category));
         }
 
-        if !self.areas.iter().any(|a| a.area == category.area) {
-            return Err("A given category has no associated area");
+        if !self.areas.contains_key(&category.area) {
+            return Err(orphan(category.category, category.area));
         }
 
-        self.categories.push(category.clone());
-        self.categories.sort_unstable();
+        self.categories.insert(category.category, category.clone());
 
-        Ok(&self.categories)
+        Ok(self.get_categories().collect())
     }
 
-    /// Adds a valid `Id` to the vector of ids if there are no duplicates.
+    /// Adds a valid `Id` to the ids if there are no duplicates.
     ///
-    /// If successful, the id list is returned as `Ok`. Otherwise `Err`.
-    pub fn add_id(&mut self, id: &Id) -> Result<&Vec<Id>, &'static str> {
-        if self.ids.contains(id) {
-            return Err("Id already exists.");
+    /// If successful, the ids are returned as `Ok`. Otherwise `Err`.
+    pub fn add_id(&mut self, id: &Id) -> Result<Vec<&Id>, Error> {
+        if self.ids.contains_key(&id.id) {
+            return Err(duplicate(id.id));
         }
 
-        if !self.categories.iter().any(|c| c.category == id.category) {
-            return Err("The given id has no associated category");
+        if !self.categories.contains_key(&id.category) {
+            return Err(orphan(id.id, id.category));
         }
 
-        self.ids.push(id.clone());
-        self.ids.sort_unstable();
+        self.ids.insert(id.id, id.clone());
 
-        Ok(&self.ids)
+        Ok(self.get_ids().collect())
     }
 
-    /// Removes a given `Area` from the vector of areas.
+    /// Removes a given `Area` from the areas.
     ///
-    /// If successful, the area list is returned as `Ok`. Otherwise `Err`.
-    pub fn remove_area(&mut self, area: &Area) -> Result<&Vec<Area>, &'static str> {
-        if !self.areas.contains(area) {
-            return Err("Given area doesn't exist in index.");
+    /// If successful, the areas are returned as `Ok`. Otherwise `Err`.
+    pub fn remove_area(&mut self, area: &Area) -> Result<Vec<&Area>, Error> {
+        if self.areas.remove(&area.area).is_none() {
+            return Err(Error::NotFound(area.area.to_string()));
         }
 
-        self.areas.retain(|a| a != area);
+        self.categories.retain(|_, category| category.area != area.area);
+        self.ids.retain(|_, id| id.area != area.area);
 
-        let area = area.get_area();
-
-        self.categories.retain(|category| category.get_area() != area);
-        self.ids.retain(|id| id.get_area() != area);
-
-        Ok(&self.areas)
+        Ok(self.get_areas().collect())
     }
 
-    /// Removes a given `Category` from the vector of categories.
+    /// Removes a given `Category` from the categories.
     ///
-    /// If successful, the category list is returned as `Ok`. Otherwise `Err`.
-    pub fn remove_category(&mut self, category: &Category) -> Result<&Vec<Category>, &'static str> {
-        if !self.categories.contains(category) {
-            return Err("Given area doesn't exist in index.");
+    /// If successful, the categories are returned as `Ok`. Otherwise `Err`.
+    pub fn remove_category(&mut self, category: &Category) -> Result<Vec<&Category>, Error> {
+        if self.categories.remove(&category.category).is_none() {
+            return Err(Error::NotFound(category.category.to_string()));
         }
 
-        self.categories.retain(|c| c != category);
+        self.ids.retain(|_, id| id.category != category.category);
 
-        let category = category.get_category();
+        Ok(self.get_categories().collect())
+    }
 
-        self.ids.retain(|id| id.get_category() != category);
+    /// Removes a given `Id` from the ids.
+    ///
+    /// If successful, the ids are returned as `Ok`. Otherwise `Err`.
+    pub fn remove_id(&mut self, id: &Id) -> Result<Vec<&Id>, Error> {
+        if self.ids.remove(&id.id).is_none() {
+            return Err(Error::NotFound(id.id.to_string()));
+        }
 
-        Ok(&self.categories)
+        Ok(self.get_ids().collect())
     }
 
-    /// Removes a given `Id` from the vector of ids.
+    /// Changes the title of the area, category, or id with a given number like `11.01`.
+    ///
+    /// Its number, metadata, comments, and every other entry are kept. If successful, the
+    /// renamed entry is returned as `Ok`. Otherwise `Err`, such as `Error::NotFound` if no entry
+    /// has the number or `Error::InvalidTitle` if the title can't be used as a file name.
+    ///
+    /// # Example
     ///
-    /// If successful, the id list is returned as `Ok`. Otherwise `Err`.
-    pub fn remove_id(&mut self, id: &Id) -> Result<&Vec<Id>, &'static str> {
-        if !self.ids.contains(id) {
-            return Err("Given id doesn't exist in index.");
+    /// ```
+    /// use johnnydecimal::Index;
+    ///
+    /// let mut index = Index::new("10-19 Area\n11 Category\n11.01 Id\n- owner: Alice").expect("Invalid index");
+    ///
+    /// assert_eq!(index.rename("11.01", "New title").expect("Should rename").to_string(), "11.01 New title");
+    /// assert_eq!(index.to_string(), "10-19 Area\n11 Category\n11.01 New title\n- owner: Alice");
+    /// assert!(index.rename("11.02", "Missing").is_err());
+    /// ```
+    pub fn rename(&mut self, number: &str, title: &str) -> Result<Entry, Error> {
+        if title.is_empty() {
+            return Err(Error::InvalidTitle(title.to_string()));
         }
 
-        self.ids.retain(|i| i != id);
+        let not_found = || Error::NotFound(number.to_string());
 
-        Ok(&self.ids)
-    }
+        match number.parse::<Entry>()? {
+            Entry::Area(area) => {
+                let area = self.areas.get_mut(&area.area).ok_or_else(not_found)?;
 
-    /// Returns the ids of a given category
-    pub fn derive_ids_of_category(&self, category: &Category) -> Vec<Id> {
-        let mut vec: Vec<Id> = vec![];
+                Ok(Entry::Area(area.set_name(title)?.clone()))
+            }
+
+            Entry::Category(category) => {
+                let category = self.categories.get_mut(&category.category).ok_or_else(not_found)?;
 
-        for id in self.ids.iter().filter(|e| e.category == category.category) {
-            vec.push(id.clone())
+                Ok(Entry::Category(category.set_name(title)?.clone()))
+            }
+
+            Entry::Id(id) => {
+                let id = self.ids.get_mut(&id.id).ok_or_else(not_found)?;
+
+                Ok(Entry::Id(id.set_name(title)?.clone()))
+            }
         }
+    }
 
-        vec.clone()
+    /// Returns the ids of a given category
+    pub fn derive_ids_of_category(&self, category: &Category) -> Vec<Id> {
+        self.ids.range(category.category.range()).map(|(_, id)| id.clone()).collect()
     }
 
     /// Returns the categories of a given area
     pub fn derive_categories_of_area(&self, area: &Area) -> Vec<Category> {
-        let mut vec: Vec<Category> = vec![];
+        self.categories.range(area.area.range()).map(|(_, category)| category.clone()).collect()
+    }
 
-        for category in self.categories.iter().filter(|e| e.area == area.area) {
-            vec.push(category.clone());
-        }
+    /// Returns every entry of the `Index` in order.
+    pub(crate) fn entries(&self) -> Vec<Entry> {
+        let mut entries = vec![];
 
-        vec.clone()
+        self.walk(|step| match step {
+            Step::Area(area) => entries.push(Entry::Area(area.clone())),
+            Step::Category(category) => entries.push(Entry::Category(category.clone())),
+            Step::Id(id) => entries.push(Entry::Id(id.clone())),
+            Step::Leave => {}
+        });
+
+        entries
     }
+
+    /// Walks the tree of the `Index`, visiting each area, then its categories, then their ids.
+    ///
+    /// `Step::Leave` follows the children of every area, so formats can close their groups.
+    pub(crate) fn walk<'a>(&'a self, mut visit: impl FnMut(Step<'a>)) {
+        for area in self.areas.values() {
+            visit(Step::Area(area));
+
+            for category in self.categories.range(area.area.range()).map(|(_, category)| category) {
+                visit(Step::Category(category));
+
+                for id in self.ids.range(category.category.range()).map(|(_, id)| id) {
+                    visit(Step::Id(id));
+                }
+            }
+
+            visit(Step::Leave);
+        }
+    }
+}
+
+/// A step of `Index::walk`.
+pub(crate) enum Step<'a> {
+    Area(&'a Area),
+    Category(&'a Category),
+    Id(&'a Id),
+    Leave,
 }
 
-/// Based on https://stackoverflow.com/a/46767732
-fn has_duplicate<T>(iter: T) -> bool
+/// Returns the first item that was already seen. Based on https://stackoverflow.com/a/46767732
+fn find_duplicate<T>(iter: T) -> Option<T::Item>
 where
     T: IntoIterator,
-    T::Item: Ord,
+    T::Item: Ord + Clone,
 {
     let mut uniq = BTreeSet::new();
 
-    !iter.into_iter().all(move |x| uniq.insert(x))
+    iter.into_iter().find(move |x| !uniq.insert(x.clone()))
+}
+
+/// Creates an `Error::Duplicate` for a given number outside of an index file.
+fn duplicate(number: impl fmt::Display) -> Error {
+    Error::Duplicate {
+        number: number.to_string(),
+        line: None,
+    }
+}
+
+/// Creates an `Error::Orphan` for a given number and parent outside of an index file.
+fn orphan(number: impl fmt::Display, parent: impl fmt::Display) -> Error {
+    Error::Orphan {
+        number: number.to_string(),
+        parent: parent.to_string(),
+        line: None,
+    }
 }
 
 impl PartialEq for Index {
@@ -887,25 +1529,53 @@ impl fmt::Display for Index {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut result = String::new();
 
-        for area in &self.areas {
-            result.push_str(&area.to_string());
+        self.walk(|step| match step {
+            Step::Area(area) => push_entry(&mut result, area, &area.metadata, &area.comments),
+            Step::Category(category) => push_entry(&mut result, category, &category.metadata, &category.comments),
+            Step::Id(id) => push_entry(&mut result, id, &id.metadata, &id.comments),
+            Step::Leave => {}
+        });
+
+        for comment in &self.trailing_comments {
+            result.push_str(comment);
             result.push('\n');
+        }
+
+        write!(f, "{}", result.trim_end_matches('\n'))
+    }
+}
 
-            let categories = self.derive_categories_of_area(area);
+/// Pushes an entry line with its comments and metadata lines to a given index string.
+fn push_entry(result: &mut String, entry: &impl fmt::Display, metadata: &Metadata, comments: &Comments) {
+    for comment in &comments.above {
+        result.push_str(comment);
+        result.push('\n');
+    }
 
-            for category in categories {
-                result.push_str(&category.to_string());
-                result.push('\n');
+    result.push_str(&entry.to_string());
 
-                let ids = self.derive_ids_of_category(&category);
+    if let Some(comment) = &comments.inline {
+        result.push(' ');
+        result.push_str(comment);
+    }
 
-                for id in ids {
-                    result.push_str(&id.to_string());
-                    result.push('\n');
-                }
-            }
-        }
+    result.push('\n');
 
-        write!(f, "{}", result.trim_end_matches('\n'))
+    for line in metadata_lines(metadata, comments) {
+        result.push_str(&line);
+        result.push('\n');
     }
 }
+
+/// Returns the `- key: value` lines of the metadata of an entry, with their inline comments.
+pub(crate) fn metadata_lines(metadata: &Metadata, comments: &Comments) -> Vec<String> {
+    metadata
+        .to_string()
+        .lines()
+        .zip(metadata.iter())
+        .map(|(line, (key, _))| match comments.get_metadata_inline(key) {
+            Some(comment) => format!("{line} {comment}"),
+            None => line.to_string(),
+        })
+        .collect()
+}
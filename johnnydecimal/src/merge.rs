@@ -0,0 +1,267 @@
+//! Three-way merge of `Index` values at the level of entries.
+
+use crate::{Comments, Entry, Index, Metadata};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The result of `Index::merge`: the merged `Index` and every conflict found.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::Index;
+///
+/// let base = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("Invalid index");
+/// let ours = Index::new("10-19 Area\n11 Category\n11.01 Id\n11.02 Ours").expect("Invalid index");
+/// let theirs = Index::new("10-19 Area\n11 Renamed\n11.01 Id").expect("Invalid index");
+/// let merge = Index::merge(&base, &ours, &theirs);
+///
+/// assert!(!merge.has_conflicts());
+/// assert_eq!(merge.get_index().to_string(), "10-19 Area\n11 Renamed\n11.01 Id\n11.02 Ours");
+/// ```
+#[derive(Debug)]
+pub struct Merge {
+    index: Index,
+    conflicts: Vec<Conflict>,
+}
+
+/// A change that `Index::merge` couldn't merge on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// The same number was added with a different title or metadata on each side.
+    ///
+    /// The merged `Index` keeps ours.
+    Claimed {
+        /// The entry in ours.
+        ours: Entry,
+        /// The entry in theirs.
+        theirs: Entry,
+    },
+
+    /// An entry was changed differently on each side, like the same id retitled twice.
+    ///
+    /// The merged `Index` keeps ours.
+    Changed {
+        /// The entry in the base.
+        base: Entry,
+        /// The entry in ours.
+        ours: Entry,
+        /// The entry in theirs.
+        theirs: Entry,
+    },
+
+    /// An entry was removed on one side and changed on the other.
+    ///
+    /// The merged `Index` keeps the changed entry.
+    Removed {
+        /// The entry in the base.
+        base: Entry,
+        /// The changed entry.
+        changed: Entry,
+    },
+
+    /// An entry was added under a parent that was removed on the other side.
+    ///
+    /// The merged `Index` keeps the parent.
+    Orphaned {
+        /// The entry that needs the parent.
+        entry: Entry,
+        /// The parent that was kept.
+        parent: Entry,
+    },
+}
+
+impl Index {
+    /// Merges the changes from a `base` to `ours` and from a `base` to `theirs`.
+    ///
+    /// Entries are merged by number. A side that didn't change an entry takes the change from the
+    /// other side, including retitles, metadata, comments, and removals. Changes that can't be
+    /// merged are returned as conflicts, and each conflicting entry gets a `// Conflict:` comment
+    /// in the merged `Index`, so the result is always a valid index.
+    pub fn merge(base: &Index, ours: &Index, theirs: &Index) -> Merge {
+        let bases = by_number(base);
+        let ourses = by_number(ours);
+        let theirses = by_number(theirs);
+
+        let mut numbers: Vec<&Entry> = bases.values().chain(ourses.values()).chain(theirses.values()).collect();
+
+        numbers.sort_by_key(|entry| entry.key());
+        numbers.dedup_by_key(|entry| entry.get_number());
+
+        let mut merged: Vec<Entry> = vec![];
+        let mut conflicts = vec![];
+
+        for number in numbers.iter().map(|entry| entry.get_number()) {
+            let (b, o, t) = (bases.get(&number), ourses.get(&number), theirses.get(&number));
+
+            let kept = if same(o, b) {
+                t
+            } else if same(t, b) || same(o, t) {
+                o
+            } else {
+                match (b, o, t) {
+                    (None, Some(o), Some(t)) => conflicts.push(Conflict::Claimed { ours: o.clone(), theirs: t.clone() }),
+                    (Some(b), Some(o), Some(t)) => conflicts.push(Conflict::Changed {
+                        base: b.clone(),
+                        ours: o.clone(),
+                        theirs: t.clone(),
+                    }),
+                    (Some(b), Some(changed), None) | (Some(b), None, Some(changed)) => conflicts.push(Conflict::Removed {
+                        base: b.clone(),
+                        changed: changed.clone(),
+                    }),
+                    _ => {}
+                }
+
+                o.or(t)
+            };
+
+            if let Some(entry) = kept {
+                merged.push(entry.clone());
+            }
+        }
+
+        // Ids can keep a category whose area was removed, so parents are restored twice.
+        for _ in 0..2 {
+            let mut parents = vec![];
+
+            for entry in &merged {
+                let Some(parent) = parent_number(entry) else {
+                    continue;
+                };
+
+                if merged.iter().chain(&parents).any(|other| other.get_number() == parent) {
+                    continue;
+                }
+
+                if let Some(parent) = [&ourses, &theirses, &bases].iter().find_map(|side| side.get(&parent)) {
+                    conflicts.push(Conflict::Orphaned {
+                        entry: entry.clone(),
+                        parent: parent.clone(),
+                    });
+
+                    parents.push(parent.clone());
+                }
+            }
+
+            merged.extend(parents);
+        }
+
+        for conflict in &conflicts {
+            let number = conflict.get_entry().get_number();
+
+            if let Some(entry) = merged.iter_mut().find(|entry| entry.get_number() == number) {
+                comments_mut(entry).above.push(format!("// Conflict: {conflict}"));
+            }
+        }
+
+        let mut index = Index::default();
+
+        for entry in merged {
+            match entry {
+                Entry::Area(area) => {
+                    index.areas.insert(area.area, area);
+                }
+                Entry::Category(category) => {
+                    index.categories.insert(category.category, category);
+                }
+                Entry::Id(id) => {
+                    index.ids.insert(id.id, id);
+                }
+            }
+        }
+
+        index.trailing_comments = if ours.trailing_comments == base.trailing_comments {
+            theirs.trailing_comments.clone()
+        } else {
+            ours.trailing_comments.clone()
+        };
+
+        Merge { index, conflicts }
+    }
+}
+
+impl Merge {
+    /// Returns the merged `Index`.
+    pub fn get_index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Returns every conflict, in the order of their entries.
+    pub fn get_conflicts(&self) -> &Vec<Conflict> {
+        &self.conflicts
+    }
+
+    /// Returns true if any change couldn't be merged.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// Consumes the `Merge`, returning the merged `Index`.
+    pub fn into_index(self) -> Index {
+        self.index
+    }
+}
+
+impl Conflict {
+    /// Returns the entry of the merged `Index` that the conflict is about.
+    pub fn get_entry(&self) -> &Entry {
+        match self {
+            Self::Claimed { ours, .. } | Self::Changed { ours, .. } => ours,
+            Self::Removed { changed, .. } => changed,
+            Self::Orphaned { parent, .. } => parent,
+        }
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Claimed { ours, theirs } => write!(f, "`{}` was added as `{ours}` in ours and `{theirs}` in theirs", ours.get_number()),
+            Self::Changed { base, ours, theirs } => write!(f, "`{base}` was changed to `{ours}` in ours and `{theirs}` in theirs"),
+            Self::Removed { base, changed } => write!(f, "`{base}` was removed on one side and changed to `{changed}` on the other"),
+            Self::Orphaned { entry, parent } => write!(f, "`{entry}` was added under `{parent}`, which was removed on the other side"),
+        }
+    }
+}
+
+/// Returns the entries of an `Index` by number.
+fn by_number(index: &Index) -> HashMap<String, Entry> {
+    index.entries().into_iter().map(|entry| (entry.get_number(), entry)).collect()
+}
+
+/// Returns true if both sides have the same entry, or neither has it.
+fn same(a: Option<&Entry>, b: Option<&Entry>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => content(a) == content(b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Returns everything that can change about an entry with the same number.
+fn content(entry: &Entry) -> (&str, &Metadata, &Comments) {
+    match entry {
+        Entry::Area(area) => (&area.name, &area.metadata, &area.comments),
+        Entry::Category(category) => (&category.name, &category.metadata, &category.comments),
+        Entry::Id(id) => (&id.name, &id.metadata, &id.comments),
+    }
+}
+
+/// Returns the comments of an entry to change.
+fn comments_mut(entry: &mut Entry) -> &mut Comments {
+    match entry {
+        Entry::Area(area) => &mut area.comments,
+        Entry::Category(category) => &mut category.comments,
+        Entry::Id(id) => &mut id.comments,
+    }
+}
+
+/// Returns the number of the parent of an entry, if it has one.
+fn parent_number(entry: &Entry) -> Option<String> {
+    match entry {
+        Entry::Area(_) => None,
+        Entry::Category(category) => Some(category.area.to_string()),
+        Entry::Id(id) => Some(id.category.to_string()),
+    }
+}
@@ -0,0 +1,336 @@
+//! Typed numbers of areas, categories, and ids.
+
+use crate::{Error, Expected, ParseError};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// `10-19`
+///
+/// An `AreaNumber` is the number `a0-a9` of an `Area`, stored as its digit `a` = `[0..9]`.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::{AreaNumber, CategoryNumber};
+///
+/// let area: AreaNumber = "10-19".parse().expect("Invalid area number");
+///
+/// assert_eq!(area.to_string(), "10-19");
+/// assert_eq!(area, AreaNumber::try_from(1).expect("Invalid area number"));
+/// assert_eq!(area.next().map(|area| area.to_string()), Some("20-29".to_string()));
+/// assert_eq!(area.children().next(), "10".parse::<CategoryNumber>().ok());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AreaNumber(u8);
+
+/// `11`
+///
+/// A `CategoryNumber` is the number `ac` of a `Category`, where `ac` = `[00..99]`.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::CategoryNumber;
+///
+/// let category: CategoryNumber = "11".parse().expect("Invalid category number");
+///
+/// assert_eq!(category, CategoryNumber::try_from(11).expect("Invalid category number"));
+/// assert_eq!(category.get_area().to_string(), "10-19");
+/// assert_eq!(category.previous().map(|category| category.to_string()), Some("10".to_string()));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryNumber(u8);
+
+/// `11.01`
+///
+/// An `IdNumber` is the number `ac.id` of an `Id`, where id = `[00..99]`.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::IdNumber;
+///
+/// let id: IdNumber = "11.01".parse().expect("Invalid id number");
+///
+/// assert_eq!(id, IdNumber::try_from((11, 1)).expect("Invalid id number"));
+/// assert_eq!(id.get_category().to_string(), "11");
+/// assert_eq!(id.get_id(), 1);
+/// assert_eq!(id.next().map(|id| id.to_string()), Some("11.02".to_string()));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IdNumber {
+    category: CategoryNumber,
+    id: u8,
+}
+
+impl AreaNumber {
+    /// Creates an `AreaNumber` from a digit already known to be in `[0..9]`.
+    pub(crate) fn from_digit(digit: char) -> Self {
+        Self(digit.to_digit(10).unwrap_or_default() as u8)
+    }
+
+    /// Returns the area after this one, or `None` after `90-99`.
+    pub fn next(self) -> Option<Self> {
+        Self::try_from(self.0 + 1).ok()
+    }
+
+    /// Returns the area before this one, or `None` before `00-09`.
+    pub fn previous(self) -> Option<Self> {
+        self.0.checked_sub(1).map(Self)
+    }
+
+    /// Returns the categories `a0` to `a9` that belong to this area.
+    pub fn children(self) -> impl Iterator<Item = CategoryNumber> {
+        (self.0 * 10..=self.0 * 10 + 9).map(CategoryNumber)
+    }
+
+    /// Returns the range `a0..=a9` of categories that belong to this area, for ordered maps.
+    pub(crate) fn range(self) -> RangeInclusive<CategoryNumber> {
+        CategoryNumber(self.0 * 10)..=CategoryNumber(self.0 * 10 + 9)
+    }
+}
+
+impl CategoryNumber {
+    /// Creates a `CategoryNumber` from two digits already known to be in `[0..9]`.
+    pub(crate) fn from_digits(a: char, c: char) -> Self {
+        Self((a.to_digit(10).unwrap_or_default() * 10 + c.to_digit(10).unwrap_or_default()) as u8)
+    }
+
+    /// Returns the area `a0-a9` that this category belongs to.
+    pub fn get_area(self) -> AreaNumber {
+        AreaNumber(self.0 / 10)
+    }
+
+    /// Returns the category after this one, or `None` after `99`.
+    ///
+    /// The next category may belong to the next area.
+    pub fn next(self) -> Option<Self> {
+        Self::try_from(self.0 + 1).ok()
+    }
+
+    /// Returns the category before this one, or `None` before `00`.
+    pub fn previous(self) -> Option<Self> {
+        self.0.checked_sub(1).map(Self)
+    }
+
+    /// Returns the ids `ac.00` to `ac.99` that belong to this category.
+    pub fn children(self) -> impl Iterator<Item = IdNumber> {
+        (0..=99).map(move |id| IdNumber { category: self, id })
+    }
+
+    /// Returns the range `ac.00..=ac.99` of ids that belong to this category, for ordered maps.
+    pub(crate) fn range(self) -> RangeInclusive<IdNumber> {
+        IdNumber { category: self, id: 0 }..=IdNumber { category: self, id: 99 }
+    }
+}
+
+impl IdNumber {
+    /// Creates an `IdNumber` from a category and two digits already known to be in `[0..9]`.
+    pub(crate) fn from_digits(category: CategoryNumber, i: char, d: char) -> Self {
+        Self {
+            category,
+            id: CategoryNumber::from_digits(i, d).0,
+        }
+    }
+
+    /// Returns the area `a0-a9` that this id belongs to.
+    pub fn get_area(self) -> AreaNumber {
+        self.category.get_area()
+    }
+
+    /// Returns the category `ac` that this id belongs to.
+    pub fn get_category(self) -> CategoryNumber {
+        self.category
+    }
+
+    /// Returns the `id` in `ac.id`.
+    pub fn get_id(self) -> u8 {
+        self.id
+    }
+
+    /// Returns the id after this one in the same category, or `None` after `ac.99`.
+    pub fn next(self) -> Option<Self> {
+        Self::try_from((self.category.0, self.id + 1)).ok()
+    }
+
+    /// Returns the id with the same `id` in another category, for renumbering categories.
+    pub(crate) fn with_category(self, category: CategoryNumber) -> Self {
+        Self { category, id: self.id }
+    }
+
+    /// Returns the id before this one in the same category, or `None` before `ac.00`.
+    pub fn previous(self) -> Option<Self> {
+        self.id.checked_sub(1).map(|id| Self { category: self.category, id })
+    }
+}
+
+impl TryFrom<u8> for AreaNumber {
+    type Error = Error;
+
+    /// Creates an `AreaNumber` from its digit `a` in `a0-a9`, so `1` is `10-19`.
+    fn try_from(digit: u8) -> Result<Self, Error> {
+        if digit > 9 {
+            return Err(number_error(digit, Expected::AreaNumber, "Given area digit is not in [0..9]"));
+        }
+
+        Ok(Self(digit))
+    }
+}
+
+impl TryFrom<u8> for CategoryNumber {
+    type Error = Error;
+
+    fn try_from(category: u8) -> Result<Self, Error> {
+        if category > 99 {
+            return Err(number_error(category, Expected::CategoryNumber, "Given category is not in [00..99]"));
+        }
+
+        Ok(Self(category))
+    }
+}
+
+impl TryFrom<(u8, u8)> for IdNumber {
+    type Error = Error;
+
+    /// Creates an `IdNumber` from its category `ac` and id in `ac.id`.
+    fn try_from((category, id): (u8, u8)) -> Result<Self, Error> {
+        let category = CategoryNumber::try_from(category)?;
+
+        if id > 99 {
+            return Err(number_error(id, Expected::IdNumber, "Given id is not in [00..99]"));
+        }
+
+        Ok(Self { category, id })
+    }
+}
+
+impl From<AreaNumber> for u8 {
+    fn from(area: AreaNumber) -> Self {
+        area.0
+    }
+}
+
+impl From<CategoryNumber> for u8 {
+    fn from(category: CategoryNumber) -> Self {
+        category.0
+    }
+}
+
+impl FromStr for AreaNumber {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self, Error> {
+        let chars: Vec<char> = str.chars().collect();
+
+        let [a, '0', '-', b, '9'] = chars.as_slice() else {
+            return Err(parse_error(str, Expected::AreaNumber, "Given area number does not follow a0-a9"));
+        };
+
+        match (a.to_digit(10), a == b) {
+            (Some(digit), true) => Ok(Self(digit as u8)),
+            _ => Err(parse_error(str, Expected::AreaNumber, "Given area number does not follow a0-a9")),
+        }
+    }
+}
+
+impl FromStr for CategoryNumber {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self, Error> {
+        match digits(str) {
+            Some(category) => Ok(Self(category)),
+            None => Err(parse_error(str, Expected::CategoryNumber, "Given category number does not follow [00-99]")),
+        }
+    }
+}
+
+impl FromStr for IdNumber {
+    type Err = Error;
+
+    fn from_str(str: &str) -> Result<Self, Error> {
+        let pair = str.split_once('.').and_then(|(category, id)| Some((digits(category)?, digits(id)?)));
+
+        match pair {
+            Some((category, id)) => Ok(Self {
+                category: CategoryNumber(category),
+                id,
+            }),
+            None => Err(parse_error(str, Expected::IdNumber, "Given id number does not follow ac.id")),
+        }
+    }
+}
+
+impl fmt::Display for AreaNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{0}0-{0}9", self.0)
+    }
+}
+
+impl fmt::Display for CategoryNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}", self.0)
+    }
+}
+
+impl fmt::Display for IdNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}", self.category, self.id)
+    }
+}
+
+/// Implements comparisons with `&str` so numbers can be checked against their written form.
+macro_rules! impl_partial_eq_str {
+    ($($number:ty),*) => {
+        $(
+            impl PartialEq<str> for $number {
+                fn eq(&self, other: &str) -> bool {
+                    other.parse::<Self>().is_ok_and(|other| *self == other)
+                }
+            }
+
+            impl PartialEq<&str> for $number {
+                fn eq(&self, other: &&str) -> bool {
+                    *self == **other
+                }
+            }
+        )*
+    };
+}
+
+impl_partial_eq_str!(AreaNumber, CategoryNumber, IdNumber);
+
+/// Implements `TryFrom<&str>` so lookups like `Index::get_area` take a number or its written form.
+macro_rules! impl_try_from_str {
+    ($($number:ty),*) => {
+        $(
+            impl TryFrom<&str> for $number {
+                type Error = Error;
+
+                fn try_from(str: &str) -> Result<Self, Error> {
+                    str.parse()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_str!(AreaNumber, CategoryNumber, IdNumber);
+
+/// Returns the value of exactly two ASCII digits.
+fn digits(str: &str) -> Option<u8> {
+    match str.as_bytes() {
+        [a @ b'0'..=b'9', b @ b'0'..=b'9'] => Some((a - b'0') * 10 + (b - b'0')),
+        _ => None,
+    }
+}
+
+/// Creates an `Error::Parse` for a number that couldn't be read.
+fn parse_error(str: &str, expected: Expected, reason: &'static str) -> Error {
+    Error::Parse(ParseError::new(str, 1, expected, reason))
+}
+
+/// Creates an `Error::Parse` for a number that is out of range.
+fn number_error(number: u8, expected: Expected, reason: &'static str) -> Error {
+    parse_error(&number.to_string(), expected, reason)
+}
@@ -0,0 +1,262 @@
+//! OPML import and export of an `Index` as nested outlines, enabled by the `opml` feature.
+
+use crate::{Area, Category, Error, Id, Index, Metadata};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::fmt::{self, Write};
+
+/// The namespace of escaped metadata keys, declared as `xmlns:jd` on the `<opml>` element.
+const NAMESPACE: &str = "https://github.com/donovanglover/jd";
+
+impl Index {
+    /// Exports the `Index` as OPML, with outlines nested by area, category, and id.
+    ///
+    /// The `text` of each outline is its entry, like `11.01 Id`. Metadata is written as attributes
+    /// of the outline. Keys that aren't valid attribute names or are `text` are written in the `jd`
+    /// namespace, with other characters escaped like `jd:due_x0020_date`. Comments aren't exported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::Index;
+    ///
+    /// let index = Index::new("10-19 Area\n11 Category\n11.01 Id\n- owner: Alice").expect("Invalid index");
+    /// let opml = index.to_opml();
+    ///
+    /// assert!(opml.contains(r#"<outline text="11.01 Id" owner="Alice"/>"#));
+    /// assert_eq!(Index::from_opml(&opml).expect("Invalid OPML"), index);
+    /// ```
+    pub fn to_opml(&self) -> String {
+        let mut opml = String::new();
+
+        let _ = writeln!(opml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(opml, r#"<opml version="2.0" xmlns:jd="{NAMESPACE}">"#);
+        let _ = writeln!(opml, "  <head>");
+        let _ = writeln!(opml, "    <title>Index</title>");
+        let _ = writeln!(opml, "  </head>");
+        let _ = writeln!(opml, "  <body>");
+
+        for area in self.areas.values() {
+            let categories = self.derive_categories_of_area(area);
+
+            write_outline(&mut opml, 2, &area.to_string(), &area.metadata, categories.is_empty());
+
+            for category in &categories {
+                let ids = self.derive_ids_of_category(category);
+
+                write_outline(&mut opml, 3, &category.to_string(), &category.metadata, ids.is_empty());
+
+                for id in &ids {
+                    write_outline(&mut opml, 4, &id.to_string(), &id.metadata, true);
+                }
+
+                if !ids.is_empty() {
+                    let _ = writeln!(opml, "      </outline>");
+                }
+            }
+
+            if !categories.is_empty() {
+                let _ = writeln!(opml, "    </outline>");
+            }
+        }
+
+        let _ = writeln!(opml, "  </body>");
+        let _ = writeln!(opml, "</opml>");
+
+        opml
+    }
+
+    /// Imports an `Index` from OPML, in the shape written by `to_opml`.
+    ///
+    /// Outlines in the `<body>` are read as areas, their children as categories, and their
+    /// grandchildren as ids. Every attribute other than `text` is read as metadata, unescaping
+    /// the keys of attributes in the `jd` namespace. The outlines
+    /// are checked with the same rules as `Index::with_vecs`, and every category must be nested
+    /// in its own area, and every id in its own category.
+    pub fn from_opml(str: &str) -> Result<Self, Error> {
+        let mut reader = Reader::from_str(str);
+        let mut opml = false;
+        let mut body = false;
+        let mut depth = 0;
+        let mut open = 0;
+
+        let mut areas: Vec<Area> = vec![];
+        let mut categories: Vec<Category> = vec![];
+        let mut ids = vec![];
+
+        loop {
+            let position = reader.buffer_position();
+
+            let event = reader.read_event().map_err(|error| import(line(str, reader.error_position()), error.to_string()))?;
+            let line = line(str, position);
+
+            match event {
+                Event::Start(_) => open += 1,
+                Event::End(_) => open -= 1,
+                _ => {}
+            }
+
+            match event {
+                Event::Start(element) | Event::Empty(element) if !opml => {
+                    if element.name().as_ref() != b"opml" {
+                        return Err(import(line, "Root element must be <opml>".to_string()));
+                    }
+
+                    opml = true;
+                }
+
+                Event::Start(element) if element.name().as_ref() == b"body" => body = true,
+                Event::End(element) if element.name().as_ref() == b"body" => body = false,
+
+                Event::Start(ref element) | Event::Empty(ref element) if body && element.name().as_ref() == b"outline" => {
+                    let (text, metadata) = read_outline(element).map_err(|reason| import(line, reason))?;
+                    let area = areas.last().map(|area| area.area);
+                    let category = categories.last().map(|category| category.category);
+
+                    match depth {
+                        0 => areas.push(Area {
+                            metadata,
+                            ..Area::new(&text).map_err(|error| import(line, error.to_string()))?
+                        }),
+
+                        1 => {
+                            let category = Category::new(&text).map_err(|error| import(line, error.to_string()))?;
+
+                            check_parent(line, category.category, category.area, area)?;
+                            categories.push(Category { metadata, ..category });
+                        }
+
+                        2 => {
+                            let id = Id::new(&text).map_err(|error| import(line, error.to_string()))?;
+
+                            check_parent(line, id.id, id.category, category)?;
+                            ids.push(Id { metadata, ..id });
+                        }
+
+                        _ => return Err(import(line, format!("`{text}` is nested deeper than an id"))),
+                    }
+
+                    if matches!(event, Event::Start(_)) {
+                        depth += 1;
+                    }
+                }
+
+                Event::End(element) if body && element.name().as_ref() == b"outline" => depth -= 1,
+                Event::Eof if open > 0 => return Err(import(line, "Document ends before every element is closed".to_string())),
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if !opml {
+            return Err(import(None, "Root element must be <opml>".to_string()));
+        }
+
+        Self::with_vecs(&areas, &categories, &ids)
+    }
+}
+
+/// Writes an outline at a given depth, closing it if it has no children.
+fn write_outline(opml: &mut String, depth: usize, text: &str, metadata: &Metadata, empty: bool) {
+    let _ = write!(opml, "{}<outline text=\"{}\"", "  ".repeat(depth), escape(text));
+
+    for (key, value) in metadata.iter() {
+        if is_attribute(key) {
+            let _ = write!(opml, " {key}=\"{}\"", escape(value));
+        } else {
+            let _ = write!(opml, " jd:{}=\"{}\"", escape_key(key), escape(value));
+        }
+    }
+
+    let _ = writeln!(opml, "{}", if empty { "/>" } else { ">" });
+}
+
+/// Returns the `text` and metadata of an outline.
+fn read_outline(element: &BytesStart) -> Result<(String, Metadata), String> {
+    let mut text = None;
+    let mut metadata = Metadata::default();
+
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|error| error.to_string())?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+        let value = attribute.unescape_value().map_err(|error| error.to_string())?;
+
+        if key == "text" {
+            text = Some(value.to_string());
+        } else if let Some(key) = key.strip_prefix("jd:") {
+            let key = unescape_key(key).ok_or_else(|| format!("Attribute jd:{key} is not a valid escaped metadata key"))?;
+
+            metadata.set(&key, &value).map_err(|error| error.to_string())?;
+        } else {
+            metadata.set(&key, &value).map_err(|error| error.to_string())?;
+        }
+    }
+
+    text.map(|text| (text, metadata)).ok_or_else(|| "Outline must have a text attribute".to_string())
+}
+
+/// Returns an `Error::Import` if an entry isn't nested in its parent.
+fn check_parent<T: PartialEq + fmt::Display>(line: Option<usize>, number: impl fmt::Display, parent: T, nested: Option<T>) -> Result<(), Error> {
+    match nested {
+        Some(nested) if nested == parent => Ok(()),
+        _ => Err(import(line, format!("`{number}` must be nested in `{parent}`"))),
+    }
+}
+
+/// Returns true if a metadata key can be written as an attribute name.
+fn is_attribute(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    key != "text" && chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Escapes a metadata key as the local part of an attribute name, writing every character that
+/// isn't an ASCII letter, digit, `-`, or `.` as `_xHHHH_`, and a leading digit, `-`, or `.` too.
+fn escape_key(key: &str) -> String {
+    let mut escaped = String::new();
+
+    for (i, c) in key.chars().enumerate() {
+        if c.is_ascii_alphabetic() || (i > 0 && (c.is_ascii_digit() || matches!(c, '-' | '.'))) {
+            escaped.push(c);
+        } else {
+            let _ = write!(escaped, "_x{:04X}_", u32::from(c));
+        }
+    }
+
+    escaped
+}
+
+/// Unescapes a metadata key written by `escape_key`, returning `None` if an escape is invalid.
+fn unescape_key(escaped: &str) -> Option<String> {
+    let mut key = String::new();
+    let mut rest = escaped;
+
+    while let Some(start) = rest.find("_x") {
+        let (hex, after) = rest[start + 2..].split_once('_')?;
+
+        if hex.len() < 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        key.push_str(&rest[..start]);
+        key.push(char::from_u32(u32::from_str_radix(hex, 16).ok()?)?);
+        rest = after;
+    }
+
+    key.push_str(rest);
+
+    Some(key)
+}
+
+/// Returns the line of a byte position in some text.
+fn line(str: &str, position: u64) -> Option<usize> {
+    let position = usize::try_from(position).unwrap_or(str.len()).min(str.len());
+
+    Some(str.as_bytes()[..position].iter().filter(|byte| **byte == b'\n').count() + 1)
+}
+
+/// Creates an `Error::Import` for OPML.
+fn import(line: Option<usize>, reason: String) -> Error {
+    Error::Import { format: "OPML", line, reason }
+}
@@ -0,0 +1,309 @@
+//! Markdown and HTML renderers for an `Index`.
+
+use crate::{Comments, Index, Metadata};
+use std::fmt::Write;
+
+/// Options for `Index::to_markdown` and `Index::to_html`.
+///
+/// By default, only numbers and titles are rendered, Markdown uses nested lists, and the HTML
+/// page is titled `Index`.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::{Index, RenderOptions};
+///
+/// let index = Index::new("10-19 Area\n- owner: Alice\n11 Category").expect("Invalid index");
+/// let options = RenderOptions::new().with_metadata(true);
+///
+/// assert_eq!(index.to_markdown(&options), "- 10-19 Area\n  - **owner**: Alice\n  - 11 Category\n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderOptions {
+    metadata: bool,
+    comments: bool,
+    headings: bool,
+    title: String,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            metadata: false,
+            comments: false,
+            headings: false,
+            title: "Index".to_string(),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Creates the default `RenderOptions`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include the `- key: value` metadata of each entry.
+    pub fn with_metadata(mut self, metadata: bool) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Include the text of comments above and at the end of each entry.
+    pub fn with_comments(mut self, comments: bool) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    /// Render Markdown as headings `#`, `##`, and `###` instead of nested lists.
+    pub fn with_headings(mut self, headings: bool) -> Self {
+        self.headings = headings;
+        self
+    }
+
+    /// Set the `<title>` of the HTML page.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+}
+
+impl Index {
+    /// Renders the `Index` as Markdown, either as nested lists or as headings.
+    ///
+    /// Metadata is rendered as `**key**: value` and comments as `*text*`, below their entry.
+    pub fn to_markdown(&self, options: &RenderOptions) -> String {
+        let mut markdown = String::new();
+
+        for area in self.areas.values() {
+            markdown_entry(&mut markdown, options, 0, &area.to_string(), &area.metadata, &area.comments);
+
+            for category in self.derive_categories_of_area(area) {
+                markdown_entry(&mut markdown, options, 1, &category.to_string(), &category.metadata, &category.comments);
+
+                for id in self.derive_ids_of_category(&category) {
+                    markdown_entry(&mut markdown, options, 2, &id.to_string(), &id.metadata, &id.comments);
+                }
+            }
+        }
+
+        if options.comments {
+            for comment in &self.trailing_comments {
+                let _ = writeln!(markdown, "*{}*{}", escape_markdown(&comment_text(comment)), if options.headings { "\n" } else { "" });
+            }
+        }
+
+        let length = markdown.trim_end().len();
+
+        markdown.truncate(length);
+
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+
+        markdown
+    }
+
+    /// Renders the `Index` as a standalone HTML page of nested lists.
+    ///
+    /// Each entry has an anchor with its number, so `page.html#11.01` links to the id `11.01`.
+    /// Metadata is rendered as a `<dl>` and comments as `<p class="comment">`, below their entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::{Index, RenderOptions};
+    ///
+    /// let index = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("Invalid index");
+    /// let html = index.to_html(&RenderOptions::new());
+    ///
+    /// assert!(html.contains(r##"<li id="11.01"><a href="#11.01">11.01</a> Id</li>"##));
+    /// ```
+    pub fn to_html(&self, options: &RenderOptions) -> String {
+        let mut html = String::new();
+
+        let _ = writeln!(html, "<!DOCTYPE html>");
+        let _ = writeln!(html, "<html lang=\"en\">");
+        let _ = writeln!(html, "<head>");
+        let _ = writeln!(html, "<meta charset=\"utf-8\">");
+        let _ = writeln!(html, "<title>{}</title>", escape_html(&options.title));
+        let _ = writeln!(html, "</head>");
+        let _ = writeln!(html, "<body>");
+        let _ = writeln!(html, "<h1>{}</h1>", escape_html(&options.title));
+
+        if !self.areas.is_empty() {
+            let _ = writeln!(html, "<ul>");
+
+            for area in self.areas.values() {
+                html_open(&mut html, options, &area.area.to_string(), &area.name, &area.metadata, &area.comments);
+
+                let categories = self.derive_categories_of_area(area);
+
+                if !categories.is_empty() {
+                    let _ = write!(html, "\n<ul>\n");
+
+                    for category in categories {
+                        html_open(&mut html, options, &category.category.to_string(), &category.name, &category.metadata, &category.comments);
+
+                        let ids = self.derive_ids_of_category(&category);
+
+                        if !ids.is_empty() {
+                            let _ = write!(html, "\n<ul>\n");
+
+                            for id in ids {
+                                html_open(&mut html, options, &id.id.to_string(), &id.name, &id.metadata, &id.comments);
+                                let _ = writeln!(html, "</li>");
+                            }
+
+                            let _ = write!(html, "</ul>");
+                        }
+
+                        let _ = writeln!(html, "</li>");
+                    }
+
+                    let _ = write!(html, "</ul>");
+                }
+
+                let _ = writeln!(html, "</li>");
+            }
+
+            let _ = writeln!(html, "</ul>");
+        }
+
+        if options.comments {
+            for comment in &self.trailing_comments {
+                let _ = writeln!(html, "<p class=\"comment\">{}</p>", escape_html(&comment_text(comment)));
+            }
+        }
+
+        let _ = writeln!(html, "</body>");
+        let _ = writeln!(html, "</html>");
+
+        html
+    }
+}
+
+/// Writes an entry, its comments, and its metadata at a given depth of Markdown.
+fn markdown_entry(markdown: &mut String, options: &RenderOptions, depth: usize, title: &str, metadata: &Metadata, comments: &Comments) {
+    let (comments, metadata) = details(options, metadata, comments);
+
+    if options.headings {
+        let _ = writeln!(markdown, "{} {}\n", "#".repeat(depth + 1), escape_markdown(title));
+
+        for text in &comments {
+            let _ = writeln!(markdown, "*{text}*\n");
+        }
+
+        for text in &metadata {
+            let _ = writeln!(markdown, "- {text}");
+        }
+
+        if !metadata.is_empty() {
+            markdown.push('\n');
+        }
+
+        return;
+    }
+
+    let indent = "  ".repeat(depth);
+
+    let _ = writeln!(markdown, "{indent}- {}", escape_markdown(title));
+
+    for text in &comments {
+        let _ = writeln!(markdown, "{indent}  - *{text}*");
+    }
+
+    for text in &metadata {
+        let _ = writeln!(markdown, "{indent}  - {text}");
+    }
+}
+
+/// Returns the escaped Markdown of the comments and metadata to render for an entry.
+fn details(options: &RenderOptions, metadata: &Metadata, comments: &Comments) -> (Vec<String>, Vec<String>) {
+    let mut texts = vec![];
+    let mut pairs = vec![];
+
+    if options.comments {
+        for comment in comments.above.iter().chain(&comments.inline).chain(comments.metadata.values()) {
+            texts.push(escape_markdown(&comment_text(comment)));
+        }
+    }
+
+    if options.metadata {
+        for (key, value) in metadata.iter() {
+            pairs.push(format!("**{}**: {}", escape_markdown(key), escape_markdown(value)));
+        }
+    }
+
+    (texts, pairs)
+}
+
+/// Opens the `<li>` of an entry with its anchor, comments, and metadata, without a line break.
+fn html_open(html: &mut String, options: &RenderOptions, number: &str, name: &str, metadata: &Metadata, comments: &Comments) {
+    let number = escape_html(number);
+
+    let _ = write!(html, "<li id=\"{number}\"><a href=\"#{number}\">{number}</a> {}", escape_html(name));
+
+    if options.comments {
+        for comment in comments.above.iter().chain(&comments.inline).chain(comments.metadata.values()) {
+            let _ = write!(html, "\n<p class=\"comment\">{}</p>", escape_html(&comment_text(comment)));
+        }
+    }
+
+    if options.metadata && !metadata.is_empty() {
+        let _ = write!(html, "\n<dl>");
+
+        for (key, value) in metadata.iter() {
+            let _ = write!(html, "\n<dt>{}</dt><dd>{}</dd>", escape_html(key), escape_html(value));
+        }
+
+        let _ = write!(html, "\n</dl>");
+    }
+}
+
+/// Returns the text of a `// line` or `/* block */` comment without its markers.
+fn comment_text(comment: &str) -> String {
+    let text = match comment.strip_prefix("//") {
+        Some(text) => text,
+        None => comment.trim_start_matches("/*").trim_end_matches("*/"),
+    };
+
+    text.lines()
+        .map(|line| line.trim().trim_start_matches('*').trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Escapes the characters of some text that have a meaning in Markdown.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|') {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Escapes the characters of some text that have a meaning in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
@@ -0,0 +1,151 @@
+//! Renumbering of categories and ids that keeps their titles, metadata, and comments.
+
+use crate::{orphan, Category, CategoryNumber, Error, Id, IdNumber, Index};
+
+impl Index {
+    /// Moves an `Id` to another `Category`, giving it the next free number there.
+    ///
+    /// Its title, metadata, and comments are kept. If successful, the moved `Id` is returned as
+    /// `Ok`. Otherwise `Err`, such as `Error::Full` if the category has no free id left.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::Index;
+    ///
+    /// let mut index = Index::new("10-19 Area\n11 Category\n11.03 Id\n12 Other\n12.01 First").expect("Invalid index");
+    /// let id = index.get_id("11.03").expect("Id should exist").clone();
+    /// let category = index.get_category("12").expect("Category should exist").clone();
+    ///
+    /// assert_eq!(index.move_id(&id, &category).expect("Should move").to_string(), "12.02 Id");
+    /// assert_eq!(index.to_string(), "10-19 Area\n11 Category\n12 Other\n12.01 First\n12.02 Id");
+    /// ```
+    pub fn move_id(&mut self, id: &Id, category: &Category) -> Result<&Id, Error> {
+        let Some(category) = self.categories.get(&category.category) else {
+            return Err(Error::NotFound(category.category.to_string()));
+        };
+
+        let number = self.next_free_id(category).ok_or_else(|| Error::Full(category.category.to_string()))?;
+
+        let Some(id) = self.ids.remove(&id.id) else {
+            return Err(Error::NotFound(id.id.to_string()));
+        };
+
+        Ok(self.ids.entry(number).or_insert(renumbered(id, number)))
+    }
+
+    /// Inserts an `Id` at its number, shifting later ids of its category up by one to make room.
+    ///
+    /// Only the ids up to the first free number are shifted, so `11.03` becomes `11.04` if it's
+    /// taken, `11.04` becomes `11.05` if that's taken too, and so on. If successful, the shifted ids
+    /// are returned as `Ok` pairs of before and after, from the highest number down, which is an
+    /// order they can be renamed in without clobbering each other. Nothing changes if the shift
+    /// would go past `.99`, which returns `Error::Overflow`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::{Id, Index};
+    ///
+    /// let mut index = Index::new("10-19 Area\n11 Category\n11.01 First\n11.02 Second\n11.04 Fourth").expect("Invalid index");
+    /// let id = Id::new("11.01 New").expect("Invalid id");
+    /// let shifted = index.insert_id_at(&id).expect("Should insert");
+    ///
+    /// assert_eq!(shifted[0].1.to_string(), "11.03 Second");
+    /// assert_eq!(index.to_string(), "10-19 Area\n11 Category\n11.01 New\n11.02 First\n11.03 Second\n11.04 Fourth");
+    /// ```
+    pub fn insert_id_at(&mut self, id: &Id) -> Result<Vec<(Id, Id)>, Error> {
+        if !self.categories.contains_key(&id.category) {
+            return Err(orphan(id.id, id.category));
+        }
+
+        let taken = run(id.id, |number| self.ids.contains_key(number), IdNumber::next)?;
+        let mut shifted = vec![];
+
+        for (from, to) in taken.into_iter().rev() {
+            if let Some(from) = self.ids.remove(&from) {
+                let to = renumbered(from.clone(), to);
+
+                self.ids.insert(to.id, to.clone());
+                shifted.push((from, to));
+            }
+        }
+
+        self.ids.insert(id.id, id.clone());
+
+        Ok(shifted)
+    }
+
+    /// Inserts a `Category` at its number, shifting later categories of its area up by one to make
+    /// room.
+    ///
+    /// Like `Index::insert_id_at`, only the categories up to the first free number are shifted,
+    /// and they're returned as `Ok` pairs of before and after from the highest number down. The
+    /// ids of a shifted category are renumbered with it, so `11.01` becomes `12.01` when `11`
+    /// becomes `12`. Nothing changes if the shift would go past `x9`, which returns
+    /// `Error::Overflow`.
+    pub fn insert_category_at(&mut self, category: &Category) -> Result<Vec<(Category, Category)>, Error> {
+        if !self.areas.contains_key(&category.area) {
+            return Err(orphan(category.category, category.area));
+        }
+
+        let next = |number: CategoryNumber| number.next().filter(|next| next.get_area() == number.get_area());
+        let taken = run(category.category, |number| self.categories.contains_key(number), next)?;
+        let mut shifted = vec![];
+
+        for (from, to) in taken.into_iter().rev() {
+            let Some(from) = self.categories.remove(&from) else {
+                continue;
+            };
+
+            let ids: Vec<IdNumber> = self.ids.range(from.category.range()).map(|(number, _)| *number).collect();
+
+            for number in ids {
+                if let Some(id) = self.ids.remove(&number) {
+                    let number = number.with_category(to);
+
+                    self.ids.insert(number, renumbered(id, number));
+                }
+            }
+
+            let to = Category { category: to, ..from.clone() };
+
+            self.categories.insert(to.category, to.clone());
+            shifted.push((from, to));
+        }
+
+        self.categories.insert(category.category, category.clone());
+
+        Ok(shifted)
+    }
+}
+
+/// Returns the taken numbers from a given number up to the first free one, each paired with the
+/// number after it.
+///
+/// Returns `Error::Overflow` if every number up to the last one is taken.
+fn run<T: Copy + ToString>(start: T, taken: impl Fn(&T) -> bool, next: impl Fn(T) -> Option<T>) -> Result<Vec<(T, T)>, Error> {
+    let mut run = vec![];
+    let mut number = start;
+
+    while taken(&number) {
+        let Some(after) = next(number) else {
+            return Err(Error::Overflow(number.to_string()));
+        };
+
+        run.push((number, after));
+        number = after;
+    }
+
+    Ok(run)
+}
+
+/// Returns an `Id` with a new number, keeping everything else.
+fn renumbered(id: Id, number: IdNumber) -> Id {
+    Id {
+        area: number.get_area(),
+        category: number.get_category(),
+        id: number,
+        ..id
+    }
+}
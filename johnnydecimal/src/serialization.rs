@@ -0,0 +1,384 @@
+//! `Serialize` and `Deserialize` implementations, enabled by the `serde` feature.
+//!
+//! Numbers are written as their canonical strings, such as `"11.01"`. Areas, categories, and ids
+//! are written as structured objects, and can be read from either an object or their canonical
+//! string `"11.01 Title"`. Use `as_string` to always write them as strings.
+
+use crate::{Area, AreaNumber, Category, CategoryNumber, Comments, Error, Id, IdNumber, Index, Metadata};
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Serializes a value as its `Display` string and deserializes it with `FromStr`.
+///
+/// Use it with `#[serde(with = "johnnydecimal::as_string")]` to write an `Area`, `Category`, or
+/// `Id` as its canonical string `"11.01 Title"` instead of a structured object. Metadata and
+/// comments aren't part of the canonical string, so they're left out.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::Id;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Bookmark {
+///     #[serde(with = "johnnydecimal::as_string")]
+///     id: Id,
+/// }
+///
+/// let bookmark = Bookmark { id: Id::new("11.01 Id").expect("Invalid id") };
+///
+/// assert_eq!(serde_json::to_string(&bookmark).expect("Invalid JSON"), r#"{"id":"11.01 Id"}"#);
+/// ```
+pub mod as_string {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    /// Serializes a value as its `Display` string.
+    pub fn serialize<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    /// Deserializes a value from a string with `FromStr`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` with `Display` and `FromStr` for numbers.
+macro_rules! impl_serde_str {
+    ($($number:ty),*) => {
+        $(
+            impl Serialize for $number {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    as_string::serialize(self, serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $number {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    as_string::deserialize(deserializer)
+                }
+            }
+        )*
+    };
+}
+
+impl_serde_str!(AreaNumber, CategoryNumber, IdNumber);
+
+/// The structured form of an `Area`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AreaObject {
+    area: AreaNumber,
+    name: String,
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    metadata: Metadata,
+    #[serde(default, skip_serializing_if = "Comments::is_empty")]
+    comments: Comments,
+}
+
+/// The structured form of a `Category`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct CategoryObject {
+    category: CategoryNumber,
+    name: String,
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    metadata: Metadata,
+    #[serde(default, skip_serializing_if = "Comments::is_empty")]
+    comments: Comments,
+}
+
+/// The structured form of an `Id`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct IdObject {
+    id: IdNumber,
+    name: String,
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    metadata: Metadata,
+    #[serde(default, skip_serializing_if = "Comments::is_empty")]
+    comments: Comments,
+}
+
+/// An entry written as either its canonical string or its structured form.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Form<T> {
+    String(String),
+    Object(T),
+}
+
+/// The structured form of an `Index`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IndexObject {
+    #[serde(default)]
+    areas: Vec<Area>,
+    #[serde(default)]
+    categories: Vec<Category>,
+    #[serde(default)]
+    ids: Vec<Id>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trailing_comments: Vec<String>,
+}
+
+impl From<&Area> for AreaObject {
+    fn from(area: &Area) -> Self {
+        Self {
+            area: area.area,
+            name: area.name.clone(),
+            metadata: area.metadata.clone(),
+            comments: area.comments.clone(),
+        }
+    }
+}
+
+impl TryFrom<AreaObject> for Area {
+    type Error = Error;
+
+    fn try_from(object: AreaObject) -> Result<Self, Error> {
+        let mut area = Self {
+            area: object.area,
+            ..Default::default()
+        };
+
+        area.set_name(&object.name)?;
+        area.metadata = object.metadata;
+        area.comments = object.comments;
+
+        Ok(area)
+    }
+}
+
+impl Serialize for Area {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AreaObject::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Area {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let object = match Form::<AreaObject>::deserialize(deserializer)? {
+            Form::String(string) => return Self::from_str(&string).map_err(de::Error::custom),
+            Form::Object(object) => object,
+        };
+
+        Self::try_from(object).map_err(de::Error::custom)
+    }
+}
+
+impl From<&Category> for CategoryObject {
+    fn from(category: &Category) -> Self {
+        Self {
+            category: category.category,
+            name: category.name.clone(),
+            metadata: category.metadata.clone(),
+            comments: category.comments.clone(),
+        }
+    }
+}
+
+impl TryFrom<CategoryObject> for Category {
+    type Error = Error;
+
+    fn try_from(object: CategoryObject) -> Result<Self, Error> {
+        let mut category = Self {
+            area: object.category.get_area(),
+            category: object.category,
+            ..Default::default()
+        };
+
+        category.set_name(&object.name)?;
+        category.metadata = object.metadata;
+        category.comments = object.comments;
+
+        Ok(category)
+    }
+}
+
+impl Serialize for Category {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CategoryObject::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Category {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let object = match Form::<CategoryObject>::deserialize(deserializer)? {
+            Form::String(string) => return Self::from_str(&string).map_err(de::Error::custom),
+            Form::Object(object) => object,
+        };
+
+        Self::try_from(object).map_err(de::Error::custom)
+    }
+}
+
+impl From<&Id> for IdObject {
+    fn from(id: &Id) -> Self {
+        Self {
+            id: id.id,
+            name: id.name.clone(),
+            metadata: id.metadata.clone(),
+            comments: id.comments.clone(),
+        }
+    }
+}
+
+impl TryFrom<IdObject> for Id {
+    type Error = Error;
+
+    fn try_from(object: IdObject) -> Result<Self, Error> {
+        let mut id = Self {
+            area: object.id.get_area(),
+            category: object.id.get_category(),
+            id: object.id,
+            ..Default::default()
+        };
+
+        id.set_name(&object.name)?;
+        id.metadata = object.metadata;
+        id.comments = object.comments;
+
+        Ok(id)
+    }
+}
+
+impl Serialize for Id {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        IdObject::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let object = match Form::<IdObject>::deserialize(deserializer)? {
+            Form::String(string) => return Self::from_str(&string).map_err(de::Error::custom),
+            Form::Object(object) => object,
+        };
+
+        Self::try_from(object).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Index {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        IndexObject {
+            areas: self.areas.values().cloned().collect(),
+            categories: self.categories.values().cloned().collect(),
+            ids: self.ids.values().cloned().collect(),
+            trailing_comments: self.trailing_comments.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Index {
+    /// Deserializes an `Index`, failing on duplicates and orphans like `Index::with_vecs`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let object = IndexObject::deserialize(deserializer)?;
+        let mut index = Self::with_vecs(&object.areas, &object.categories, &object.ids).map_err(de::Error::custom)?;
+
+        for comment in &object.trailing_comments {
+            crate::validate_comment(comment).map_err(de::Error::custom)?;
+        }
+
+        index.trailing_comments = object.trailing_comments;
+
+        Ok(index)
+    }
+}
+
+impl Serialize for Metadata {
+    /// Serializes `Metadata` as a map, keeping the order of its keys.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Metadata {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(MetadataVisitor)
+    }
+}
+
+/// Reads `Metadata` from a map, checking each key and value with `Metadata::set`.
+struct MetadataVisitor;
+
+impl<'de> Visitor<'de> for MetadataVisitor {
+    type Value = Metadata;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a map of metadata keys to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Metadata, A::Error> {
+        let mut metadata = Metadata::default();
+
+        while let Some((key, value)) = access.next_entry::<String, String>()? {
+            if metadata.get(&key).is_some() {
+                return Err(de::Error::custom(format!("Duplicate metadata key `{key}` is not allowed")));
+            }
+
+            metadata.set(&key, &value).map_err(de::Error::custom)?;
+        }
+
+        Ok(metadata)
+    }
+}
+
+impl Serialize for Comments {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CommentsObject {
+            above: self.above.clone(),
+            inline: self.inline.clone(),
+            metadata: self.metadata.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Comments {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let object = CommentsObject::deserialize(deserializer)?;
+
+        for comment in object.above.iter().chain(&object.inline).chain(object.metadata.values()) {
+            crate::validate_comment(comment).map_err(de::Error::custom)?;
+        }
+
+        Ok(Self {
+            above: object.above,
+            inline: object.inline,
+            metadata: object.metadata,
+        })
+    }
+}
+
+/// The structured form of `Comments`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CommentsObject {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    above: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inline: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
+}
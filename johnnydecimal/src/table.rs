@@ -0,0 +1,305 @@
+//! CSV and TSV import and export of an `Index`, one row per entry.
+
+use crate::{Area, AreaNumber, Category, CategoryNumber, Error, Id, IdNumber, Index, Metadata};
+use std::collections::HashMap;
+
+/// The columns before the metadata columns of every table.
+const COLUMNS: [&str; 5] = ["kind", "area", "category", "id", "title"];
+
+/// An area, category, or id read from a row, with the number of its row.
+enum Row {
+    Area(usize, Area),
+    Category(usize, Category),
+    Id(usize, Id),
+}
+
+impl Index {
+    /// Exports the `Index` as CSV, with one row per area, category, and id.
+    ///
+    /// The columns are `kind`, `area`, `category`, `id`, and `title`, followed by one column per
+    /// metadata key. Comments aren't exported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use johnnydecimal::Index;
+    ///
+    /// let index = Index::new("10-19 Area\n- owner: Alice\n11 Category\n11.01 Id").expect("Invalid index");
+    ///
+    /// assert_eq!(
+    ///     index.to_csv(),
+    ///     "kind,area,category,id,title,owner\narea,10-19,,,Area,Alice\ncategory,10-19,11,,Category,\nid,10-19,11,11.01,Id,\n"
+    /// );
+    /// ```
+    pub fn to_csv(&self) -> String {
+        self.to_table(',')
+    }
+
+    /// Exports the `Index` as TSV, with the same columns as `Index::to_csv`.
+    pub fn to_tsv(&self) -> String {
+        self.to_table('\t')
+    }
+
+    /// Imports an `Index` from CSV, in the shape written by `Index::to_csv`.
+    ///
+    /// Rows can be in any order. The `area` and `category` columns may be left empty, since they
+    /// follow from the `id` or `category`. An empty metadata cell means the key isn't set.
+    ///
+    /// Problems are returned as `Error::Import` with the row where they were found, counting the
+    /// header as row 1. Duplicates and orphans are checked like `Index::with_vecs`.
+    pub fn from_csv(str: &str) -> Result<Self, Error> {
+        from_table(str, ',', "CSV")
+    }
+
+    /// Imports an `Index` from TSV, in the shape written by `Index::to_tsv`.
+    pub fn from_tsv(str: &str) -> Result<Self, Error> {
+        from_table(str, '\t', "TSV")
+    }
+
+    /// Writes the rows of the `Index` with a given separator.
+    fn to_table(&self, separator: char) -> String {
+        let mut keys: Vec<&str> = vec![];
+
+        let entries = self.areas.values().map(|area| &area.metadata);
+        let entries = entries.chain(self.categories.values().map(|category| &category.metadata));
+
+        for metadata in entries.chain(self.ids.values().map(|id| &id.metadata)) {
+            for (key, _) in metadata.iter() {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        let mut table = String::new();
+        let header: Vec<&str> = COLUMNS.iter().copied().chain(keys.iter().copied()).collect();
+
+        write_row(&mut table, separator, &header);
+
+        for area in self.areas.values() {
+            write_entry(&mut table, separator, &keys, ["area", &area.area.to_string(), "", "", &area.name], &area.metadata);
+
+            for category in self.derive_categories_of_area(area) {
+                let numbers = [category.area.to_string(), category.category.to_string()];
+
+                write_entry(&mut table, separator, &keys, ["category", &numbers[0], &numbers[1], "", &category.name], &category.metadata);
+
+                for id in self.derive_ids_of_category(&category) {
+                    let numbers = [id.area.to_string(), id.category.to_string(), id.id.to_string()];
+
+                    write_entry(&mut table, separator, &keys, ["id", &numbers[0], &numbers[1], &numbers[2], &id.name], &id.metadata);
+                }
+            }
+        }
+
+        table
+    }
+}
+
+/// Reads an `Index` from rows with a given separator.
+fn from_table(str: &str, separator: char, format: &'static str) -> Result<Index, Error> {
+    let error = |row: usize, reason: String| Error::Import {
+        format,
+        line: Some(row),
+        reason: format!("Row {row}: {reason}"),
+    };
+
+    let rows = read_rows(str, separator).map_err(|(row, reason)| error(row, reason.to_string()))?;
+    let mut rows = rows.into_iter().enumerate().map(|(i, cells)| (i + 1, cells));
+
+    let Some((_, header)) = rows.next() else {
+        return Ok(Index::default());
+    };
+
+    if header.len() < COLUMNS.len() || header.iter().zip(COLUMNS).any(|(cell, column)| !cell.trim().eq_ignore_ascii_case(column)) {
+        return Err(error(1, format!("Header must start with the columns {}", COLUMNS.join(", "))));
+    }
+
+    let keys = &header[COLUMNS.len()..];
+
+    if let Some((i, key)) = keys.iter().enumerate().find(|(i, key)| keys[..*i].iter().any(|other| other.trim() == key.trim())) {
+        return Err(error(1, format!("Header has the metadata column {} more than once, in column {}", key.trim(), COLUMNS.len() + i + 1)));
+    }
+    let mut entries = vec![];
+
+    for (row, cells) in rows {
+        if cells.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+
+        if cells.len() > header.len() {
+            return Err(error(row, format!("Row has {} cells, but the header only has {}", cells.len(), header.len())));
+        }
+
+        let mut metadata = Metadata::default();
+
+        for (i, key) in keys.iter().enumerate() {
+            let value = cell(&cells, COLUMNS.len() + i);
+
+            if !value.is_empty() {
+                metadata.set(key.trim(), value).map_err(|reason| error(row, reason.to_string()))?;
+            }
+        }
+
+        entries.push(read_entry(row, &cells, metadata).map_err(|reason| error(row, reason))?);
+    }
+
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for entry in &entries {
+        let (row, number) = match entry {
+            Row::Area(row, area) => (*row, area.area.to_string()),
+            Row::Category(row, category) => (*row, category.category.to_string()),
+            Row::Id(row, id) => (*row, id.id.to_string()),
+        };
+
+        if let Some(first) = seen.insert(number.clone(), row) {
+            return Err(error(row, format!("Duplicate `{number}` is not allowed, since it's already on row {first}")));
+        }
+    }
+
+    for entry in &entries {
+        let (row, number, parent) = match entry {
+            Row::Area(..) => continue,
+            Row::Category(row, category) => (*row, category.category.to_string(), category.area.to_string()),
+            Row::Id(row, id) => (*row, id.id.to_string(), id.category.to_string()),
+        };
+
+        if !seen.contains_key(&parent) {
+            return Err(error(row, format!("`{number}` has no parent `{parent}`")));
+        }
+    }
+
+    let mut areas = vec![];
+    let mut categories = vec![];
+    let mut ids = vec![];
+
+    for entry in entries {
+        match entry {
+            Row::Area(_, area) => areas.push(area),
+            Row::Category(_, category) => categories.push(category),
+            Row::Id(_, id) => ids.push(id),
+        }
+    }
+
+    Index::with_vecs(&areas, &categories, &ids)
+}
+
+/// Reads the entry of a row from its `kind`, `area`, `category`, `id`, and `title` cells.
+fn read_entry(row: usize, cells: &[String], metadata: Metadata) -> Result<Row, String> {
+    let (kind, area, category, id, title) = (cell(cells, 0), cell(cells, 1), cell(cells, 2), cell(cells, 3), cell(cells, 4));
+
+    let area = (!area.is_empty()).then(|| area.parse::<AreaNumber>()).transpose().map_err(|error| error.to_string())?;
+    let category = (!category.is_empty()).then(|| category.parse::<CategoryNumber>()).transpose().map_err(|error| error.to_string())?;
+    let id = (!id.is_empty()).then(|| id.parse::<IdNumber>()).transpose().map_err(|error| error.to_string())?;
+
+    let mismatch = |number: &dyn std::fmt::Display, column: &str, given: &dyn std::fmt::Display| format!("`{number}` does not belong to the {column} `{given}`");
+
+    match kind.to_ascii_lowercase().as_str() {
+        "area" => {
+            let area = area.ok_or("Area rows must have an area")?;
+
+            Area::new(&format!("{area} {title}"))
+                .map(|area| Row::Area(row, Area { metadata, ..area }))
+                .map_err(|error| error.to_string())
+        }
+
+        "category" => {
+            let category = category.ok_or("Category rows must have a category")?;
+
+            if let Some(area) = area.filter(|area| *area != category.get_area()) {
+                return Err(mismatch(&category, "area", &area));
+            }
+
+            Category::new(&format!("{category} {title}"))
+                .map(|category| Row::Category(row, Category { metadata, ..category }))
+                .map_err(|error| error.to_string())
+        }
+
+        "id" => {
+            let id = id.ok_or("Id rows must have an id")?;
+
+            if let Some(category) = category.filter(|category| *category != id.get_category()) {
+                return Err(mismatch(&id, "category", &category));
+            }
+
+            if let Some(area) = area.filter(|area| *area != id.get_area()) {
+                return Err(mismatch(&id, "area", &area));
+            }
+
+            Id::new(&format!("{id} {title}")).map(|id| Row::Id(row, Id { metadata, ..id })).map_err(|error| error.to_string())
+        }
+
+        _ => Err(format!("Kind `{kind}` must be area, category, or id")),
+    }
+}
+
+/// Returns the trimmed cell of a column, or an empty cell if the row is too short.
+fn cell(cells: &[String], column: usize) -> &str {
+    cells.get(column).map_or("", |cell| cell.trim())
+}
+
+/// Writes the row of an entry with a cell for each metadata key.
+fn write_entry(table: &mut String, separator: char, keys: &[&str], cells: [&str; 5], metadata: &Metadata) {
+    let values = keys.iter().map(|key| metadata.get(key).unwrap_or_default());
+    let row: Vec<&str> = cells.into_iter().chain(values).collect();
+
+    write_row(table, separator, &row);
+}
+
+/// Writes a row, quoting cells that contain a separator, quote, or line break.
+fn write_row(table: &mut String, separator: char, cells: &[&str]) {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| {
+            if cell.contains([separator, '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        })
+        .collect();
+
+    table.push_str(&cells.join(&separator.to_string()));
+    table.push('\n');
+}
+
+/// Splits some text into rows of cells, following the quoting rules of RFC 4180.
+///
+/// Returns the row and reason if a quoted cell is never closed.
+fn read_rows(str: &str, separator: char) -> Result<Vec<Vec<String>>, (usize, &'static str)> {
+    let mut rows = vec![];
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = str.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if cell.is_empty() => quoted = true,
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                cells.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut cells));
+            }
+            c if c == separator && !quoted => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+
+    if quoted {
+        return Err((rows.len() + 1, "Quoted cell is never closed"));
+    }
+
+    if !cell.is_empty() || !cells.is_empty() {
+        cells.push(cell);
+        rows.push(cells);
+    }
+
+    Ok(rows)
+}
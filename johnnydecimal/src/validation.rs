@@ -0,0 +1,336 @@
+//! Validation of `00.00 Index.txt` that collects every problem instead of stopping at the first.
+
+use crate::lexer::{split_comments, Line};
+use crate::{entry_error, parse_metadata, Area, AreaNumber, Category, CategoryNumber, Comments, Error, Expected, Id, IdNumber, Index, ParseError};
+use std::collections::{btree_map, BTreeMap};
+use std::fmt;
+
+/// The result of `Index::validate`: a best-effort `Index` and every problem found.
+///
+/// # Example
+///
+/// ```
+/// use johnnydecimal::{Index, Severity};
+///
+/// let validation = Index::validate("20-29 Area\n10-19 Earlier\n12.01 Orphan\nUnknown line");
+///
+/// assert_eq!(validation.get_index().get_areas().len(), 2);
+/// assert_eq!(validation.get_diagnostics().len(), 3);
+/// assert_eq!(validation.get_diagnostics()[0].get_severity(), Severity::Warning);
+/// assert!(validation.has_errors());
+/// ```
+#[derive(Debug)]
+pub struct Validation {
+    index: Index,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// A single problem found while validating an index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    severity: Severity,
+    error: Error,
+}
+
+/// How serious a `Diagnostic` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The line was skipped, so `Index::new` fails.
+    Error,
+    /// The line was read, but breaks a rule of the index spec that `Index::new` allows.
+    Warning,
+}
+
+impl Index {
+    /// Validates a given `&str`, returning every problem instead of stopping at the first.
+    ///
+    /// Duplicates, orphans, invalid titles, and unknown lines are errors and are left out of the
+    /// best-effort `Index`. Entries that are out of order are warnings and are kept.
+    pub fn validate(str: &str) -> Validation {
+        let (index, diagnostics) = parse(str);
+
+        Validation { index, diagnostics }
+    }
+}
+
+impl Validation {
+    /// Returns the `Index` built from every valid entry.
+    pub fn get_index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Returns the problems found, in the order of their lines.
+    pub fn get_diagnostics(&self) -> &Vec<Diagnostic> {
+        &self.diagnostics
+    }
+
+    /// Returns `true` if any problem has `Severity::Error`.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    /// Consumes the `Validation`, returning its `Index`.
+    pub fn into_index(self) -> Index {
+        self.index
+    }
+}
+
+impl Diagnostic {
+    /// Returns how serious the problem is.
+    pub fn get_severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Returns the `Error` describing the problem.
+    pub fn get_error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Returns the 1-based line of the problem, if known.
+    pub fn get_line(&self) -> Option<usize> {
+        self.error.get_line()
+    }
+
+    /// Consumes the `Diagnostic`, returning its `Error`.
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.error)
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// The kind and number of the entry most recently read by the parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Area(AreaNumber),
+    Category(CategoryNumber),
+    Id(IdNumber),
+    /// An invalid entry, whose metadata is skipped along with it.
+    Skipped,
+}
+
+/// Parses a given `&str` into a best-effort `Index` and every problem found.
+pub(crate) fn parse(str: &str) -> (Index, Vec<Diagnostic>) {
+    let mut areas: BTreeMap<AreaNumber, Area> = BTreeMap::new();
+    let mut categories: BTreeMap<CategoryNumber, Category> = BTreeMap::new();
+    let mut ids: BTreeMap<IdNumber, Id> = BTreeMap::new();
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+    let mut last: Option<Kind> = None;
+    let mut previous: Option<Key> = None;
+    let mut comments: Vec<String> = vec![];
+
+    let lines = match split_comments(str) {
+        Ok(lines) => lines,
+        Err(error) => {
+            diagnostics.push(Diagnostic::error(Error::Parse(error)));
+
+            return (Index::default(), diagnostics);
+        }
+    };
+
+    for (i, line) in lines.into_iter().enumerate() {
+        let line_number = i + 1;
+        let offset = line.offset();
+        let Line { content, comments: line_comments, .. } = line;
+        let line = content.trim();
+        let locate = |error: ParseError| Error::Parse(error.at(line_number, offset));
+
+        if line.is_empty() {
+            comments.extend(line_comments);
+
+            continue;
+        }
+
+        if line.starts_with('-') {
+            let (key, value) = match parse_metadata(line) {
+                Ok(pair) => pair,
+                Err(error) => {
+                    diagnostics.push(Diagnostic::error(locate(error)));
+
+                    continue;
+                }
+            };
+
+            let entry = match last {
+                Some(Kind::Area(number)) => areas.get_mut(&number).map(|area| (&mut area.metadata, &mut area.comments)),
+                Some(Kind::Category(number)) => categories.get_mut(&number).map(|category| (&mut category.metadata, &mut category.comments)),
+                Some(Kind::Id(number)) => ids.get_mut(&number).map(|id| (&mut id.metadata, &mut id.comments)),
+                Some(Kind::Skipped) => continue,
+                None => None,
+            };
+
+            let Some((metadata, entry_comments)) = entry else {
+                let error = ParseError::new(line, 1, Expected::Entry, "Metadata must follow an area, category, or id");
+
+                diagnostics.push(Diagnostic::error(locate(error)));
+
+                continue;
+            };
+
+            if metadata.get(key).is_some() {
+                diagnostics.push(Diagnostic::error(Error::DuplicateMetadata {
+                    key: key.to_string(),
+                    line: Some(line_number),
+                }));
+
+                continue;
+            }
+
+            if let Err(error) = metadata.set(key, value) {
+                diagnostics.push(Diagnostic::error(error));
+            }
+
+            if !line_comments.is_empty() {
+                entry_comments.metadata.insert(key.to_string(), line_comments.join(" "));
+            }
+
+            continue;
+        }
+
+        let inline = (!line_comments.is_empty()).then(|| line_comments.join(" "));
+        let entry_comments = Comments {
+            above: std::mem::take(&mut comments),
+            inline,
+            ..Comments::default()
+        };
+
+        last = Some(Kind::Skipped);
+
+        let (error, key) = if let Ok(mut id) = Id::new(line) {
+            let key = (id.area, Some(id.category), Some(id.id));
+
+            let error = match ids.entry(id.id) {
+                btree_map::Entry::Occupied(_) => Some(duplicate(id.id, line_number)),
+                btree_map::Entry::Vacant(_) if !categories.contains_key(&id.category) => Some(orphan(id.id, id.category, line_number)),
+                btree_map::Entry::Vacant(entry) => {
+                    id.comments = entry_comments;
+                    last = Some(Kind::Id(id.id));
+                    entry.insert(id);
+
+                    None
+                }
+            };
+
+            (error, Some(key))
+        } else if let Ok(mut category) = Category::new(line) {
+            let key = (category.area, Some(category.category), None);
+
+            let error = match categories.entry(category.category) {
+                btree_map::Entry::Occupied(_) => Some(duplicate(category.category, line_number)),
+                btree_map::Entry::Vacant(_) if !areas.contains_key(&category.area) => Some(orphan(category.category, category.area, line_number)),
+                btree_map::Entry::Vacant(entry) => {
+                    category.comments = entry_comments;
+                    last = Some(Kind::Category(category.category));
+                    entry.insert(category);
+
+                    None
+                }
+            };
+
+            (error, Some(key))
+        } else if let Ok(mut area) = Area::new(line) {
+            let key = (area.area, None, None);
+
+            let error = match areas.entry(area.area) {
+                btree_map::Entry::Occupied(_) => Some(duplicate(area.area, line_number)),
+                btree_map::Entry::Vacant(entry) => {
+                    area.comments = entry_comments;
+                    last = Some(Kind::Area(area.area));
+                    entry.insert(area);
+
+                    None
+                }
+            };
+
+            (error, Some(key))
+        } else {
+            let error = match entry_error(line) {
+                Error::Parse(error) => locate(error),
+                error => error,
+            };
+
+            (Some(error), None)
+        };
+
+        if let Some(error) = error {
+            diagnostics.push(Diagnostic::error(error));
+
+            continue;
+        }
+
+        if let (Some(key), Some(previous)) = (&key, &previous) {
+            if key < previous {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    error: Error::OutOfOrder {
+                        number: number(key),
+                        previous: number(previous),
+                        line: Some(line_number),
+                    },
+                });
+            }
+        }
+
+        if key > previous {
+            previous = key;
+        }
+    }
+
+    let index = Index {
+        areas,
+        categories,
+        ids,
+        trailing_comments: comments,
+    };
+
+    (index, diagnostics)
+}
+
+impl Diagnostic {
+    /// Creates a new `Diagnostic` with `Severity::Error`.
+    fn error(error: Error) -> Self {
+        Self { severity: Severity::Error, error }
+    }
+}
+
+/// The position of an entry in a sorted index: `(area, category, id)`.
+type Key = (AreaNumber, Option<CategoryNumber>, Option<IdNumber>);
+
+/// Returns the most specific number of a `Key`.
+fn number(key: &Key) -> String {
+    match key {
+        (_, _, Some(id)) => id.to_string(),
+        (_, Some(category), None) => category.to_string(),
+        (area, None, None) => area.to_string(),
+    }
+}
+
+/// Creates an `Error::Duplicate` for a given number on a given line.
+fn duplicate(number: impl fmt::Display, line: usize) -> Error {
+    Error::Duplicate {
+        number: number.to_string(),
+        line: Some(line),
+    }
+}
+
+/// Creates an `Error::Orphan` for a given number and parent on a given line.
+fn orphan(number: impl fmt::Display, parent: impl fmt::Display, line: usize) -> Error {
+    Error::Orphan {
+        number: number.to_string(),
+        parent: parent.to_string(),
+        line: Some(line),
+    }
+}
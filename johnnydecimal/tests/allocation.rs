@@ -0,0 +1,56 @@
+//! Tests for allocating the next free category and id.
+
+use johnnydecimal::{AllocationOptions, Index, Policy};
+
+#[test]
+fn test_next_free_id() {
+    let index = Index::new("10-19 Area\n11 Category\n11.00 Meta\n11.01 First\n11.03 Third\n12 Empty\n13 Full").expect("index should be valid");
+    let categories: Vec<_> = index.get_categories().collect();
+    let (category, empty) = (categories[0], categories[1]);
+    let after_highest = AllocationOptions::new().with_policy(Policy::AfterHighest);
+    let with_reserved = AllocationOptions::new().with_skip_reserved(false);
+
+    assert_eq!(index.next_free_id(category).expect("should have a free id"), "11.02", "should fill the lowest gap");
+    assert_eq!(
+        index.next_free_id_with(category, &after_highest).expect("should have a free id"),
+        "11.04",
+        "should go after the highest"
+    );
+    assert_eq!(index.next_free_id(empty).expect("should have a free id"), "12.01", "should skip .00");
+    assert_eq!(
+        index.next_free_id_with(empty, &with_reserved).expect("should have a free id"),
+        "12.00",
+        "should allow .00 if configured"
+    );
+
+    let ids: Vec<String> = (1..=99).map(|id| format!("13.{id:02} Id")).collect();
+    let full = Index::new(&format!("10-19 Area\n13 Full\n{}", ids.join("\n"))).expect("index should be valid");
+
+    assert!(
+        full.next_free_id(full.get_categories().next().expect("should have a category")).is_none(),
+        "should return none for full categories"
+    );
+}
+
+#[test]
+fn test_next_free_category() {
+    let index = Index::new("10-19 Area\n11 First\n12 Second\n14 Fourth\n20-29 Empty\n30-39 Last\n39 Ninth").expect("index should be valid");
+    let areas: Vec<_> = index.get_areas().collect();
+    let after_highest = AllocationOptions::new().with_policy(Policy::AfterHighest);
+
+    assert_eq!(index.next_free_category(areas[0]).expect("should have a free category"), "13", "should fill the lowest gap");
+    assert_eq!(
+        index.next_free_category_with(areas[0], &after_highest).expect("should have a free category"),
+        "15",
+        "should go after the highest"
+    );
+    assert_eq!(index.next_free_category(areas[1]).expect("should have a free category"), "21", "should skip x0");
+    assert_eq!(
+        index
+            .next_free_category_with(areas[1], &AllocationOptions::new().with_skip_reserved(false))
+            .expect("should have a free category"),
+        "20",
+        "should allow x0 if configured"
+    );
+    assert!(index.next_free_category_with(areas[2], &after_highest).is_none(), "should not wrap around after x9");
+}
@@ -69,3 +69,21 @@ fn set_area_name() {
     assert!(area.set_name("/* Comment */").is_err(), "Name `/* Comment */` should fail");
     assert!(area.set_name("Testing // Comment").is_err(), "Name `Testing // Comment` should fail");
 }
+
+#[test]
+fn set_area_metadata() {
+    let mut area = Area::new("10-19 Test Area").expect("`10-19 Test Area` should be a valid area");
+
+    assert!(area.set_metadata("owner", "Alice").is_ok(), "Key `owner` should pass");
+    assert!(area.set_metadata("owner", "Bob").is_ok(), "Existing key `owner` should pass");
+    assert!(area.set_metadata("", "Value").is_err(), "Empty key should fail");
+    assert!(area.set_metadata(" owner", "Value").is_err(), "Key with white space should fail");
+    assert!(area.set_metadata("a:b", "Value").is_err(), "Key with colon should fail");
+    assert!(area.set_metadata("key", "Line\nbreak").is_err(), "Value with line break should fail");
+
+    assert_eq!(area.get_metadata().get("owner"), Some("Bob"), "should replace existing value");
+    assert_eq!(area.get_metadata().len(), 1, "should only have 1 pair");
+    assert_eq!(area.remove_metadata("owner"), Some("Bob".to_string()), "should return removed value");
+    assert!(area.get_metadata().is_empty(), "should have no pairs");
+    assert_eq!(area.to_string(), "10-19 Test Area", "metadata should not be part of the area name");
+}
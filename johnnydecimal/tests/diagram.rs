@@ -0,0 +1,53 @@
+//! Tests for the Graphviz DOT and Mermaid diagrams.
+
+use johnnydecimal::{Depth, DiagramOptions, Index};
+
+const SOURCE: &str = "10-19 Area\n   11 Category\n      11.00 Meta\n      11.01 Id\n      11.02 Second Id\n   12 Empty\n20-29 Second";
+
+#[test]
+fn test_dot() {
+    let index = Index::new(SOURCE).expect("index should be valid");
+    let dot = index.to_dot(&DiagramOptions::new());
+
+    assert!(dot.starts_with("digraph Index {\n") && dot.ends_with("}\n"), "should be a digraph");
+    assert!(dot.contains("  \"10-19\" [label=\"10-19 Area\"];\n"), "should have a node per area");
+    assert!(dot.contains("  \"10-19\" -> \"11\";\n"), "should link areas to categories");
+    assert!(dot.contains("  \"11\" -> \"11.01\";\n"), "should link categories to ids");
+
+    let clustered = index.to_dot(&DiagramOptions::new().with_clusters(true).with_fullness(true));
+
+    assert!(clustered.contains("  subgraph \"cluster_10-19\" {\n    label=\"10-19 Area\";\n"), "should cluster by area");
+    assert_eq!(clustered.matches("  }\n").count(), 2, "should close every cluster");
+    assert!(clustered.contains(r#"[label="11 Category\n2/99 ids"]"#), "should show fullness without the .00 id");
+    assert!(clustered.contains(r#"[label="12 Empty\n0/99 ids"]"#), "should show empty categories");
+}
+
+#[test]
+fn test_mermaid() {
+    let index = Index::new(SOURCE).expect("index should be valid");
+    let mermaid = index.to_mermaid(&DiagramOptions::new());
+
+    assert!(mermaid.starts_with("flowchart LR\n"), "should be a flowchart");
+    assert!(mermaid.contains("  c11 --> i11_01\n"), "should link categories to ids");
+    assert!(mermaid.contains("  i11_02[\"11.02 Second Id\"]\n"), "should name ids without dots");
+
+    let clustered = index.to_mermaid(&DiagramOptions::new().with_clusters(true).with_fullness(true));
+
+    assert!(
+        clustered.contains("  subgraph a2_cluster [\"20-29 Second\"]\n    a2[\"20-29 Second\"]\n  end\n"),
+        "should cluster by area"
+    );
+    assert!(clustered.contains("c11[\"11 Category<br>2/99 ids\"]"), "should show fullness");
+}
+
+#[test]
+fn test_diagram_depth() {
+    let index = Index::new(SOURCE).expect("index should be valid");
+    let areas = DiagramOptions::new().with_depth(Depth::Areas);
+    let categories = DiagramOptions::new().with_depth(Depth::Categories);
+
+    assert!(!index.to_dot(&areas).contains("\"11\""), "should leave out categories");
+    assert!(!index.to_mermaid(&areas).contains("c11"), "should leave out categories");
+    assert!(index.to_dot(&categories).contains("\"11\"") && !index.to_dot(&categories).contains("11.01"), "should leave out ids");
+    assert_eq!(Index::default().to_mermaid(&DiagramOptions::new()), "flowchart LR\n", "should allow empty indexes");
+}
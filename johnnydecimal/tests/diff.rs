@@ -0,0 +1,49 @@
+//! Tests for `Index::diff`.
+
+use johnnydecimal::{Change, Index};
+
+const BEFORE: &str = "10-19 Area\n11 Category\n11.01 Same\n11.02 Old title\n11.03 Moved\n11.04 Removed\n20-29 Gone";
+const AFTER: &str = "10-19 Area\n11 Category\n11.01 Same\n11.02 New title\n11.05 Added\n12 New\n12.01 Moved";
+
+#[test]
+fn test_diff_changes() {
+    let before = Index::new(BEFORE).expect("index should be valid");
+    let after = Index::new(AFTER).expect("index should be valid");
+    let diff = before.diff(&after);
+
+    let changes: Vec<(&str, String)> = diff
+        .get_changes()
+        .iter()
+        .map(|change| match change {
+            Change::Added(entry) => ("added", entry.get_number()),
+            Change::Removed(entry) => ("removed", entry.get_number()),
+            Change::Renamed { to, .. } => ("renamed", to.get_number()),
+            Change::Moved { to, .. } => ("moved", to.get_number()),
+        })
+        .collect();
+
+    let expected = [
+        ("renamed", "11.02"),
+        ("moved", "12.01"),
+        ("removed", "11.04"),
+        ("added", "11.05"),
+        ("added", "12"),
+        ("removed", "20-29"),
+    ];
+
+    assert_eq!(changes, expected.map(|(kind, number)| (kind, number.to_string())), "should find typed changes in order");
+    assert!(before.diff(&before).is_empty(), "should find no changes in the same index");
+    assert_eq!(after.diff(&before).get_changes().len(), diff.get_changes().len(), "should find the reverse changes");
+}
+
+#[test]
+fn test_diff_report() {
+    let before = Index::new("10-19 Area\n11 Category\n11.01 Old\n11.02 Moved").expect("index should be valid");
+    let after = Index::new("10-19 Area\n11 Category\n11.01 New\n11.03 Moved\n11.04 Added").expect("index should be valid");
+
+    assert_eq!(
+        before.diff(&after).to_string(),
+        "@@ renamed 11.01 @@\n-11.01 Old\n+11.01 New\n@@ moved 11.02 to 11.03 @@\n-11.02 Moved\n+11.03 Moved\n@@ added 11.04 @@\n+11.04 Added",
+        "should render like a unified diff"
+    );
+}
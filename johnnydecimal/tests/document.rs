@@ -0,0 +1,113 @@
+//! Tests for `Document`.
+
+use johnnydecimal::{Area, Category, Document, Id, Index, NodeKind};
+
+const SOURCE: &str = "// My system\r\n\r\n10-19 Area   // Inline\r\n   11 Category\r\n      - owner: Alice\r\n\r\n      11.01 Id\r\n   /* Block\r\n      comment */\r\n   12 Another\r\n20-29 Second";
+
+#[test]
+fn test_document() {
+    let document = Document::new(SOURCE).expect("document should be valid");
+
+    assert_eq!(document.to_string(), SOURCE, "should keep every byte of the source");
+
+    let kinds: Vec<NodeKind> = document.get_nodes().iter().map(|node| node.get_kind()).collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            NodeKind::Comment,
+            NodeKind::Blank,
+            NodeKind::Area,
+            NodeKind::Category,
+            NodeKind::Metadata,
+            NodeKind::Blank,
+            NodeKind::Id,
+            NodeKind::Comment,
+            NodeKind::Comment,
+            NodeKind::Category,
+            NodeKind::Area,
+        ],
+        "should classify every line"
+    );
+
+    assert_eq!(document.get_nodes()[6].get_number().as_deref(), Some("11.01"), "should get number of id line");
+    assert_eq!(document.get_nodes()[2].get_name(), Some("Area"), "should get name of area line");
+    assert_eq!(document.get_nodes()[0].get_ending(), "\r\n", "should keep line endings");
+    assert_eq!(
+        document.get_index().expect("index should be valid"),
+        Index::new(SOURCE).expect("index should be valid"),
+        "should derive index"
+    );
+
+    let unknown = Document::new("Not an entry\n").expect("unknown lines should be kept");
+
+    assert_eq!(unknown.get_nodes()[0].get_kind(), NodeKind::Unknown, "should keep unknown lines");
+    assert!(unknown.get_index().is_err(), "unknown lines should fail validation");
+    assert!(Document::new("/* Never closed").is_err(), "should fail if block comment isn't closed");
+}
+
+#[test]
+fn test_document_rename() {
+    let mut document = Document::new(SOURCE).expect("document should be valid");
+
+    document.rename("10-19", "Renamed area").expect("should rename area");
+    document.rename("11.01", "Renamed id").expect("should rename id");
+
+    assert_eq!(
+        document.to_string(),
+        SOURCE.replace("10-19 Area   ", "10-19 Renamed area   ").replace("11.01 Id", "11.01 Renamed id"),
+        "should only change titles"
+    );
+
+    assert!(document.rename("13", "Missing").is_err(), "should fail if number doesn't exist");
+    assert!(document.rename("11", "../Invalid").is_err(), "should fail if title is invalid");
+}
+
+#[test]
+fn test_document_metadata() {
+    let mut document = Document::new(SOURCE).expect("document should be valid");
+
+    document.set_metadata("11", "owner", "Bob").expect("should edit existing metadata");
+    document.set_metadata("11", "url", "https://example.com").expect("should add metadata");
+    document.set_metadata("20-29", "status", "Archived").expect("should add metadata to last line");
+
+    let expected = SOURCE.replace("- owner: Alice\r\n", "- owner: Bob\r\n      - url: https://example.com\r\n") + "\r\n- status: Archived";
+
+    assert_eq!(document.to_string(), expected, "should only add and change metadata lines");
+
+    document.remove_metadata("20-29", "status").expect("should remove metadata");
+    document.remove_metadata("11", "url").expect("should remove metadata");
+    document.set_metadata("11", "owner", "Alice").expect("should edit existing metadata");
+
+    assert_eq!(document.to_string(), SOURCE, "should restore the source");
+    assert!(document.remove_metadata("11", "missing").is_err(), "should fail if key doesn't exist");
+    assert!(document.set_metadata("11", "owner", "Bob // Alice").is_err(), "should fail if value has a comment");
+}
+
+#[test]
+fn test_document_add_and_remove() {
+    let mut document = Document::new(SOURCE).expect("document should be valid");
+
+    document.add_id(&Id::new("11.02 Second id").expect("id should be valid")).expect("should add id");
+    document.add_category(&Category::new("13 Third").expect("category should be valid")).expect("should add category");
+    document.add_area(&Area::new("30-39 Third").expect("area should be valid")).expect("should add area");
+
+    let expected = SOURCE
+        .replace("11.01 Id\r\n", "11.01 Id\r\n      11.02 Second id\r\n")
+        .replace("12 Another\r\n", "12 Another\r\n   13 Third\r\n")
+        + "\r\n30-39 Third";
+
+    assert_eq!(document.to_string(), expected, "should add entries in order with matching indentation");
+    assert!(document.add_id(&Id::new("11.02 Again").expect("id should be valid")).is_err(), "should fail on duplicates");
+
+    document.remove("30-39").expect("should remove area");
+    document.remove("13").expect("should remove category");
+    document.remove("11.02").expect("should remove id");
+
+    assert_eq!(document.to_string(), SOURCE, "should restore the source");
+
+    document.remove("10-19").expect("should remove area with children");
+
+    assert_eq!(document.to_string(), "// My system\r\n\r\n20-29 Second", "should remove children but keep comments above");
+    assert!(document.remove("10-19").is_err(), "should fail if number doesn't exist");
+}
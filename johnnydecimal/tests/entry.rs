@@ -0,0 +1,47 @@
+//! Tests for `Entry`.
+
+use johnnydecimal::{Entry, Error, Expected, Index};
+
+#[test]
+fn test_entry_from_str() {
+    let entry = |str: &str| str.parse::<Entry>().expect("should be a valid entry");
+
+    assert!(matches!(entry("10-19 Area"), Entry::Area(_)), "should detect areas");
+    assert!(matches!(entry("11 Category"), Entry::Category(_)), "should detect categories");
+    assert!(matches!(entry("11.01 Id"), Entry::Id(_)), "should detect ids");
+    assert_eq!(entry(" 11.01 Id ").to_string(), "11.01 Id", "should trim white space");
+    assert!(!entry("11.01 Id").is_reference(), "should not be a reference with a title");
+
+    assert!(matches!(entry("10-19"), Entry::Area(_)), "should accept bare area numbers");
+    assert!(matches!(entry("11"), Entry::Category(_)), "should accept bare category numbers");
+    assert!(matches!(entry("11.01"), Entry::Id(_)), "should accept bare id numbers");
+    assert!(entry("11.01").is_reference(), "should be a reference without a title");
+    assert_eq!(entry("11.01").to_string(), "11.01", "should display references as numbers");
+}
+
+#[test]
+fn test_entry_errors() {
+    let expected = |str: &str| match str.parse::<Entry>() {
+        Err(Error::Parse(error)) => error.get_expected(),
+        result => panic!("should be a parse error: {result:?}"),
+    };
+
+    assert_eq!(expected("11.1 Typo"), Expected::Id, "should explain ids with a decimal separator");
+    assert_eq!(expected("10-18 Typo"), Expected::Area, "should explain areas with a dash");
+    assert_eq!(expected("11_Typo"), Expected::Category, "should explain categories with two digits");
+    assert_eq!(expected("Just a folder"), Expected::Entry, "should explain values that match no kind");
+
+    let message = "Just a folder".parse::<Entry>().expect_err("should fail").to_string();
+
+    assert!(message.contains("neither an area a0-a9, a category ac, nor an id ac.id"), "should say why no kind matched");
+}
+
+#[test]
+fn test_get_entry() {
+    let index = Index::new("10-19 Area\n11 Category\n11.01 Id").expect("index should be valid");
+    let name = |str: &str| index.get_entry(&str.parse().expect("should be a valid entry")).map(|entry| entry.to_string());
+
+    assert_eq!(name("11.01"), Some("11.01 Id".to_string()), "should resolve references");
+    assert_eq!(name("11 Other"), Some("11 Category".to_string()), "should resolve entries by number");
+    assert_eq!(name("11.02"), None, "should not resolve missing entries");
+}
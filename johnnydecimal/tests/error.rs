@@ -0,0 +1,99 @@
+//! Tests for `Error`.
+
+use johnnydecimal::{Area, Category, Error, Expected, Id, Index};
+
+/// Returns the line, column, and expected form of a parse error.
+fn location(error: Error) -> (usize, usize, Expected) {
+    let Error::Parse(error) = error else {
+        panic!("should be a parse error: {error:?}");
+    };
+
+    (error.get_line(), error.get_column(), error.get_expected())
+}
+
+#[test]
+fn test_entry_errors() {
+    let error = |result: Result<Area, Error>| location(result.expect_err("should fail"));
+
+    assert_eq!(error(Area::new("c0-c9 Area")), (1, 1, Expected::Area), "should point at a");
+    assert_eq!(error(Area::new("10-15 Area")), (1, 5, Expected::Area), "should point at 9");
+    assert_eq!(error(Area::new("10-19")), (1, 6, Expected::Area), "should point after the end");
+    assert_eq!(error(Area::new("10-19 ../Area")), (1, 7, Expected::Area), "should point at the title");
+
+    let error = location(Category::new("1x Category").expect_err("should fail"));
+
+    assert_eq!(error, (1, 2, Expected::Category), "should point at c");
+
+    let error = location(Id::new("11.0x Id").expect_err("should fail"));
+
+    assert_eq!(error, (1, 5, Expected::Id), "should point at the second id digit");
+
+    let Err(Error::Parse(error)) = Id::new("11_01 Id") else {
+        panic!("should fail");
+    };
+
+    assert_eq!(error.get_text(), "11_01 Id", "should keep the offending text");
+    assert_eq!(error.get_reason(), "Given id does not have a decimal separator in ac.id", "should keep the reason");
+    assert_eq!(
+        error.to_string(),
+        "Line 1, column 3: Given id does not have a decimal separator in ac.id (expected `ac.id <title>`): `11_01 Id`"
+    );
+}
+
+#[test]
+fn test_index_errors() {
+    let error = |str: &str| location(Index::new(str).expect_err("should fail"));
+
+    assert_eq!(error("10-19 Area\n   11 Category\n      11.0 Id"), (3, 11, Expected::Id), "should find id on line 3");
+    assert_eq!(error("10-19 Area\n20-2 Area"), (2, 5, Expected::Area), "should find area on line 2");
+    assert_eq!(error("10-19 Area\nJust text"), (2, 1, Expected::Entry), "should expect any entry");
+    assert_eq!(error("- key: value"), (1, 1, Expected::Entry), "should expect an entry before metadata");
+    assert_eq!(error("10-19 Area\n  - no colon"), (2, 13, Expected::Metadata), "should expect metadata");
+    assert_eq!(error("10-19 Area\n  /* Never\n closed"), (2, 3, Expected::CommentEnd), "should point at the open comment");
+
+    assert_eq!(
+        Index::new("10-19 Area\n10-19 Again"),
+        Err(Error::Duplicate {
+            number: "10-19".to_string(),
+            line: Some(2)
+        }),
+        "should find duplicate on line 2"
+    );
+
+    assert_eq!(
+        Index::new("10-19 Area\n11.01 Id"),
+        Err(Error::Orphan {
+            number: "11.01".to_string(),
+            parent: "11".to_string(),
+            line: Some(2)
+        }),
+        "should find orphan on line 2"
+    );
+
+    assert_eq!(
+        Index::new("10-19 Area\n- a: 1\n- a: 2"),
+        Err(Error::DuplicateMetadata { key: "a".to_string(), line: Some(3) }),
+        "should find duplicate metadata on line 3"
+    );
+}
+
+#[test]
+fn test_index_method_errors() {
+    let mut index = Index::new("10-19 Area\n11 Category").expect("index should be valid");
+    let id = Id::new("12.01 Id").expect("id should be valid");
+
+    assert_eq!(
+        index.add_id(&id),
+        Err(Error::Orphan {
+            number: "12.01".to_string(),
+            parent: "12".to_string(),
+            line: None
+        })
+    );
+    assert_eq!(index.remove_id(&id), Err(Error::NotFound("12.01".to_string())));
+
+    let mut area = Area::new("10-19 Area").expect("area should be valid");
+
+    assert_eq!(area.set_name("../Area").map(|_| ()), Err(Error::InvalidTitle("../Area".to_string())));
+    assert!(matches!(area.set_metadata("", "value"), Err(Error::InvalidMetadata(_))), "should fail with invalid metadata");
+}
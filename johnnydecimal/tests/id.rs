@@ -81,3 +81,24 @@ fn set_id_name() {
     assert!(id.set_name("/* Comment */").is_err(), "Name `/* Comment */` should fail");
     assert!(id.set_name("Testing // Comment").is_err(), "Name `Testing // Comment` should fail");
 }
+
+#[test]
+fn set_id_comments() {
+    use johnnydecimal::Comments;
+
+    let mut id = Id::new("11.01 Test Id").expect("`11.01 Test Id` should be a valid id");
+    let mut comments = Comments::default();
+
+    assert!(comments.add_above("// Above").is_ok(), "`// Above` should pass");
+    assert!(comments.add_above("/* Above */").is_ok(), "`/* Above */` should pass");
+    assert!(comments.add_above("// Line\nbreak").is_err(), "Line comment with line break should fail");
+    assert!(comments.add_above("/* Early */ end */").is_err(), "Block comment with early end should fail");
+    assert!(comments.set_inline(Some("Inline")).is_err(), "Comment without delimiter should fail");
+    assert!(comments.set_inline(Some("// Inline")).is_ok(), "`// Inline` should pass");
+
+    id.set_comments(comments.clone());
+
+    assert_eq!(id.get_comments(), &comments, "should set comments");
+    assert_eq!(id.to_string(), "11.01 Test Id", "comments should not be part of the id name");
+    assert!(Comments::default().is_empty(), "default comments should be empty");
+}
@@ -1,51 +1,53 @@
-use johnnydecimal::Index;
+//! Tests for `Index`.
+
+use johnnydecimal::{Error, Index};
 
 #[test]
 fn test_index() {
     if let Ok(empty) = Index::new("") {
-        assert!(empty.get_areas().is_empty(), "empty index should have no areas");
-        assert!(empty.get_categories().is_empty(), "empty index should have no categories");
-        assert!(empty.get_ids().is_empty(), "empty index should have no ids");
+        assert!(empty.get_areas().next().is_none(), "empty index should have no areas");
+        assert!(empty.get_categories().next().is_none(), "empty index should have no categories");
+        assert!(empty.get_ids().next().is_none(), "empty index should have no ids");
     } else {
         panic!("empty index should pass")
     }
 
     if let Ok(empty_lines) = Index::new("\n\n\n") {
-        assert!(empty_lines.get_areas().is_empty(), "empty_lines index should have no areas");
-        assert!(empty_lines.get_categories().is_empty(), "empty_lines index should have no categories");
-        assert!(empty_lines.get_ids().is_empty(), "empty_lines index should have no ids");
+        assert!(empty_lines.get_areas().next().is_none(), "empty_lines index should have no areas");
+        assert!(empty_lines.get_categories().next().is_none(), "empty_lines index should have no categories");
+        assert!(empty_lines.get_ids().next().is_none(), "empty_lines index should have no ids");
     } else {
         panic!("empty_lines should pass")
     }
 
     if let Ok(area_only) = Index::new("20-29 Test\n30-39 Another") {
-        assert_eq!(area_only.get_areas().iter().count(), 2, "area_only index should have 2 areas");
-        assert!(area_only.get_categories().is_empty(), "area_only index should have no categories");
-        assert!(area_only.get_ids().is_empty(), "area_only index should have no ids");
+        assert_eq!(area_only.get_areas().len(), 2, "area_only index should have 2 areas");
+        assert!(area_only.get_categories().next().is_none(), "area_only index should have no categories");
+        assert!(area_only.get_ids().next().is_none(), "area_only index should have no ids");
     } else {
         panic!("area_only index should pass");
     }
 
     if let Ok(area_with_category) = Index::new("20-29 Area\n22 Category\n25 Another") {
-        assert_eq!(area_with_category.get_areas().iter().count(), 1, "area_with_category index should have 1 area");
-        assert_eq!(area_with_category.get_categories().iter().count(), 2, "area_with_category index should have 2 categories");
-        assert!(area_with_category.get_ids().is_empty(), "area_with_category index should have no ids");
+        assert_eq!(area_with_category.get_areas().len(), 1, "area_with_category index should have 1 area");
+        assert_eq!(area_with_category.get_categories().len(), 2, "area_with_category index should have 2 categories");
+        assert!(area_with_category.get_ids().next().is_none(), "area_with_category index should have no ids");
     } else {
         panic!("area_with_category index should pass");
     }
 
     if let Ok(area_with_category_and_id) = Index::new("20-29 Area\n22 Category\n22.03 Id\n22.05 Another") {
-        assert_eq!(area_with_category_and_id.get_areas().iter().count(), 1, "area_with_category_and_id index should have 1 area");
-        assert_eq!(area_with_category_and_id.get_categories().iter().count(), 1, "area_with_category_and_id index should have 1 category");
-        assert_eq!(area_with_category_and_id.get_ids().iter().count(), 2, "area_with_category_and_id index should have 2 ids");
+        assert_eq!(area_with_category_and_id.get_areas().len(), 1, "area_with_category_and_id index should have 1 area");
+        assert_eq!(area_with_category_and_id.get_categories().len(), 1, "area_with_category_and_id index should have 1 category");
+        assert_eq!(area_with_category_and_id.get_ids().len(), 2, "area_with_category_and_id index should have 2 ids");
     } else {
         panic!("area_with_category_and_id index should pass");
     }
 
     if let Ok(valid_with_empty_lines) = Index::new("\n10-19 Testing\n\n20-29 Another\n\n\n23 Category\n\n23.05 Id\n\n\n") {
-        assert_eq!(valid_with_empty_lines.get_areas().iter().count(), 2, "valid_with_empty_lines index should have 2 areas");
-        assert_eq!(valid_with_empty_lines.get_categories().iter().count(), 1, "valid_with_empty_lines index should have 1 category");
-        assert_eq!(valid_with_empty_lines.get_ids().iter().count(), 1, "valid_with_empty_lines index should have 1 id");
+        assert_eq!(valid_with_empty_lines.get_areas().len(), 2, "valid_with_empty_lines index should have 2 areas");
+        assert_eq!(valid_with_empty_lines.get_categories().len(), 1, "valid_with_empty_lines index should have 1 category");
+        assert_eq!(valid_with_empty_lines.get_ids().len(), 1, "valid_with_empty_lines index should have 1 id");
     } else {
         panic!("valid_with_empty_lines index should pass");
     }
@@ -64,10 +66,12 @@ fn test_index() {
 #[test]
 fn sort_index() {
     if let Ok(index_str_reverse) = Index::new("20-29 1 Test\n10-19 2 Another") {
-        assert_eq!(index_str_reverse.get_areas()[0].get_area(), "10-19", "should sort 10-19 before 20-29");
-        assert_eq!(index_str_reverse.get_areas()[1].get_area(), "20-29", "should sort 20-29 after 10-19");
-        assert_eq!(index_str_reverse.get_areas()[0].get_name(), "2 Another", "should not sort by name");
-        assert_eq!(index_str_reverse.get_areas()[1].get_name(), "1 Test", "should not sort by name");
+        let areas: Vec<_> = index_str_reverse.get_areas().collect();
+
+        assert_eq!(areas[0].get_area(), "10-19", "should sort 10-19 before 20-29");
+        assert_eq!(areas[1].get_area(), "20-29", "should sort 20-29 after 10-19");
+        assert_eq!(areas[0].get_name(), "2 Another", "should not sort by name");
+        assert_eq!(areas[1].get_name(), "1 Test", "should not sort by name");
     } else {
         panic!("index_str_reverse should pass");
     }
@@ -88,18 +92,22 @@ fn sort_index() {
     ];
 
     if let Ok(index_str_mix) = Index::new(&lines.join("\n")) {
-        assert_eq!(index_str_mix.get_areas()[0].get_area(), "10-19", "should have 10-19 first");
-        assert_eq!(index_str_mix.get_areas()[1].get_name(), "Area B", "should have Area B second");
-        assert_eq!(index_str_mix.get_areas()[2].get_area(), "30-39", "should have 30-39 third");
-        assert_eq!(index_str_mix.get_areas()[3].get_name(), "Area C", "should have Area C fourth");
-        assert_eq!(index_str_mix.get_categories()[0].get_area(), "20-29", "should have a category with area 20-29 first");
-        assert_eq!(index_str_mix.get_categories()[1].get_category(), "25", "should have category 25 second");
-        assert_eq!(index_str_mix.get_categories()[2].get_name(), "Category A", "should have Category A third");
-        assert_eq!(index_str_mix.get_categories()[3].get_category(), "37", "should have category 37 fourth");
-        assert_eq!(index_str_mix.get_ids()[0].get_area(), "20-29", "should have an id with area 20-29 first");
-        assert_eq!(index_str_mix.get_ids()[1].get_category(), "25", "should have an id with category 25 second");
-        assert_eq!(index_str_mix.get_ids()[2].get_id(), "34.05", "should have id 34.05 third");
-        assert_eq!(index_str_mix.get_ids()[3].get_name(), "Id B", "should have Id B fourth");
+        let areas: Vec<_> = index_str_mix.get_areas().collect();
+        let categories: Vec<_> = index_str_mix.get_categories().collect();
+        let ids: Vec<_> = index_str_mix.get_ids().collect();
+
+        assert_eq!(areas[0].get_area(), "10-19", "should have 10-19 first");
+        assert_eq!(areas[1].get_name(), "Area B", "should have Area B second");
+        assert_eq!(areas[2].get_area(), "30-39", "should have 30-39 third");
+        assert_eq!(areas[3].get_name(), "Area C", "should have Area C fourth");
+        assert_eq!(categories[0].get_area(), "20-29", "should have a category with area 20-29 first");
+        assert_eq!(categories[1].get_category(), "25", "should have category 25 second");
+        assert_eq!(categories[2].get_name(), "Category A", "should have Category A third");
+        assert_eq!(categories[3].get_category(), "37", "should have category 37 fourth");
+        assert_eq!(ids[0].get_area(), "20-29", "should have an id with area 20-29 first");
+        assert_eq!(ids[1].get_category(), "25", "should have an id with category 25 second");
+        assert_eq!(ids[2].get_id(), "34.05", "should have id 34.05 third");
+        assert_eq!(ids[3].get_name(), "Id B", "should have Id B fourth");
     } else {
         panic!("index_str_mix should pass");
     }
@@ -148,9 +156,9 @@ fn test_with_vecs() {
 
     assert!(Index::with_vecs(&areas, &categories, &duplicate_ids).is_err(), "duplicate ids should fail");
 
-    assert!(Index::with_vecs(&vec![], &categories, &vec![]).is_err(), "should fail if category_only index");
-    assert!(Index::with_vecs(&vec![], &vec![], &ids).is_err(), "should fail if id_only index");
-    assert!(Index::with_vecs(&areas, &vec![], &ids).is_err(), "should fail if area and id only");
+    assert!(Index::with_vecs(&[], &categories, &[]).is_err(), "should fail if category_only index");
+    assert!(Index::with_vecs(&[], &[], &ids).is_err(), "should fail if id_only index");
+    assert!(Index::with_vecs(&areas, &[], &ids).is_err(), "should fail if area and id only");
 }
 
 #[test]
@@ -220,14 +228,14 @@ fn test_remove_area() {
         let area_1 = Area::new("10-19 Test").expect("`10-19 Test` should be a valid area");
         let area_2 = Area::new("20-29 Test").expect("`20-29 Test` should be a valid area");
 
-        assert!(index.get_areas().contains(&area_2), "`20-29 Test` should be in areas");
+        assert!(index.get_areas().any(|entry| *entry == area_2), "`20-29 Test` should be in areas");
 
         assert!(index.remove_area(&area_1).is_err(), "`10-19 Test` should NOT be a valid area to remove");
         assert!(index.remove_area(&area_2).is_ok(), "`20-29 Test` should be a valid area to remove");
 
-        assert!(!index.get_areas().contains(&area_2), "`20-29 Test` should NOT be in areas");
-        assert_eq!(index.get_categories().iter().count(), 1, "categories should have 1 remaining");
-        assert!(index.get_ids().is_empty(), "ids should be empty");
+        assert!(!index.get_areas().any(|entry| *entry == area_2), "`20-29 Test` should NOT be in areas");
+        assert_eq!(index.get_categories().len(), 1, "categories should have 1 remaining");
+        assert!(index.get_ids().next().is_none(), "ids should be empty");
     } else {
         panic!("area_with_category_and_id index should pass");
     }
@@ -241,13 +249,13 @@ fn test_remove_category() {
         let category_1 = Category::new("21 Test").expect("`21 Test` should be a valid category");
         let category_2 = Category::new("22 Test").expect("`22 Test` should be a valid category");
 
-        assert!(index.get_categories().contains(&category_2), "`22 Test` should be in categories");
+        assert!(index.get_categories().any(|entry| *entry == category_2), "`22 Test` should be in categories");
 
         assert!(index.remove_category(&category_1).is_err(), "`21 Test` should NOT be a valid category to remove");
         assert!(index.remove_category(&category_2).is_ok(), "`22 Test` should be a valid category to remove");
 
-        assert!(!index.get_categories().contains(&category_2), "`22 Test` should NOT be in categories");
-        assert!(index.get_ids().is_empty(), "ids should be empty");
+        assert!(!index.get_categories().any(|entry| *entry == category_2), "`22 Test` should NOT be in categories");
+        assert!(index.get_ids().next().is_none(), "ids should be empty");
     } else {
         panic!("area_with_category_and_id index should pass");
     }
@@ -261,12 +269,12 @@ fn test_remove_id() {
         let id_1 = Id::new("22.01 Test").expect("`22.01 Test` should be a valid id");
         let id_2 = Id::new("22.03 Test").expect("`22.03 Test` should be a valid id");
 
-        assert!(index.get_ids().contains(&id_2), "`22.03 Test` should be in ids");
+        assert!(index.get_ids().any(|entry| *entry == id_2), "`22.03 Test` should be in ids");
 
         assert!(index.remove_id(&id_1).is_err(), "`22.01 Test` should NOT be a valid id to remove");
         assert!(index.remove_id(&id_2).is_ok(), "`22.03 Test` should be a valid id to remove");
 
-        assert!(!index.get_ids().contains(&id_2), "ids should not contain `22.03`");
+        assert!(!index.get_ids().any(|entry| *entry == id_2), "ids should not contain `22.03`");
     } else {
         panic!("area_with_category_and_id index should pass");
     }
@@ -410,3 +418,162 @@ fn test_display_index() {
 
     assert_eq!(index.to_string(), "20-29 Area\n22 Category\n22.01 Id", "should return an index string in order")
 }
+
+#[test]
+fn test_metadata() {
+    let index = Index::new("10-19 Area\n- owner: Alice\n11 Category\n   - url: https://example.com\n11.01 Id\n- location:  Desk drawer \n- empty:").expect("index with metadata should be valid");
+
+    let area = index.get_areas().next().expect("should have an area");
+    let category = index.get_categories().next().expect("should have a category");
+    let id = index.get_ids().next().expect("should have an id");
+
+    assert_eq!(area.get_metadata().get("owner"), Some("Alice"), "area should have owner metadata");
+    assert_eq!(category.get_metadata().get("url"), Some("https://example.com"), "category should keep `//` in values");
+    assert_eq!(id.get_metadata().get("location"), Some("Desk drawer"), "id value should be trimmed");
+    assert_eq!(id.get_metadata().get("empty"), Some(""), "id should allow empty values");
+    assert_eq!(id.get_metadata().len(), 2, "id should have 2 pairs");
+
+    assert!(Index::new("- key: value\n10-19 Area").is_err(), "should fail if metadata has no entry");
+    assert!(Index::new("10-19 Area\n- no separator").is_err(), "should fail if metadata has no colon");
+    assert!(Index::new("10-19 Area\n- : value").is_err(), "should fail if metadata has no key");
+    assert!(Index::new("10-19 Area\n- key: one\n- key: two").is_err(), "should fail if metadata key is duplicated");
+}
+
+#[test]
+fn test_display_metadata() {
+    let mut index = Index::new("20-29 Area\n22 Category\n- b: 2\n- a: 1\n22.01 Id").expect("index should be a valid index");
+
+    assert_eq!(index.to_string(), "20-29 Area\n22 Category\n- b: 2\n- a: 1\n22.01 Id", "should keep metadata order");
+
+    let mut area = index.get_areas().next().expect("should have an area").clone();
+
+    area.set_metadata("owner", "Bob").expect("metadata should be valid");
+    index.remove_area(&area).expect("area should be removed");
+    index.add_area(&area).expect("area should be added");
+
+    assert_eq!(index.to_string(), "20-29 Area\n- owner: Bob", "should write area metadata");
+}
+
+#[test]
+fn test_metadata_with_comments() {
+    let mut index = Index::new("10-19 Area").expect("index should be a valid index");
+    let mut area = index.get_areas().next().expect("should have an area").clone();
+
+    assert!(
+        matches!(area.set_metadata("note", "see // later"), Err(Error::InvalidMetadata(_))),
+        "should fail if value has a line comment"
+    );
+    assert!(
+        matches!(area.set_metadata("note", "/* draft */"), Err(Error::InvalidMetadata(_))),
+        "should fail if value starts with a block comment"
+    );
+    assert!(matches!(area.set_metadata("// note", "value"), Err(Error::InvalidMetadata(_))), "should fail if key has a comment");
+    assert!(
+        Index::from_csv("kind,area,category,id,title,note\narea,10-19,,,Area,see // later").is_err(),
+        "should fail to import comments in metadata"
+    );
+
+    area.set_metadata("url", "https://example.com/a//b").expect("metadata should be valid");
+    index.remove_area(&area).expect("area should be removed");
+    index.add_area(&area).expect("area should be added");
+
+    let parsed = Index::new(&index.to_string()).expect("written index should be valid");
+
+    assert_eq!(
+        parsed.get_areas().next().expect("should have an area").get_metadata().get("url"),
+        Some("https://example.com/a//b"),
+        "should read back values with `//` inside words"
+    );
+}
+
+#[test]
+fn test_comments() {
+    let lines = [
+        "// Header",
+        "10-19 Area // Inline",
+        "   /* Block",
+        "      spanning lines */",
+        "   11 Category /* Inline block */",
+        "   - url: https://example.com // Note",
+        "      11.01 Id",
+        "/* Multi */ /* Many */",
+        "// Footer",
+    ];
+
+    let index = Index::new(&lines.join("\n")).expect("index with comments should be valid");
+    let area = index.get_areas().next().expect("should have an area");
+    let category = index.get_categories().next().expect("should have a category");
+    let id = index.get_ids().next().expect("should have an id");
+
+    assert_eq!(area.get_comments().get_above(), &vec!["// Header".to_string()], "area should have header comment");
+    assert_eq!(area.get_comments().get_inline(), Some("// Inline"), "area should have inline comment");
+    assert_eq!(
+        category.get_comments().get_above(),
+        &vec!["/* Block\n      spanning lines */".to_string()],
+        "category should keep multi-line block comment"
+    );
+    assert_eq!(category.get_comments().get_inline(), Some("/* Inline block */"), "category should have inline block comment");
+    assert_eq!(category.get_metadata().get("url"), Some("https://example.com"), "category should keep url metadata");
+    assert_eq!(category.get_comments().get_metadata_inline("url"), Some("// Note"), "category should keep metadata comment");
+    assert!(id.get_comments().is_empty(), "id should have no comments");
+    assert_eq!(index.get_trailing_comments().len(), 3, "index should have 3 trailing comments");
+
+    assert_eq!(
+        index.to_string(),
+        "// Header\n10-19 Area // Inline\n/* Block\n      spanning lines */\n11 Category /* Inline block */\n- url: https://example.com // Note\n11.01 Id\n/* Multi */\n/* Many */\n// Footer",
+        "should write metadata comments back on their lines"
+    );
+
+    assert!(Index::new("10-19 Area\n/* Never closed").is_err(), "should fail if block comment isn't closed");
+    assert!(Index::new("10-19 Area\n11 Category//No space").is_err(), "should fail if comment isn't separated from title");
+}
+
+#[test]
+fn test_display_comments() {
+    let source = "// Header\n10-19 Area // Inline\n/* Block\n   comment */\n11 Category\n- key: value\n11.01 Id /* Inline */\n// Footer";
+    let index = Index::new(source).expect("index should be a valid index");
+
+    assert_eq!(index.to_string(), source, "should write comments back in place");
+    assert_eq!(Index::new(&index.to_string()), Ok(index), "should parse its own output");
+}
+
+#[test]
+fn test_get_by_number() {
+    let index = Index::new("10-19 Area\n11 Category\n11.01 Id\n20-29 Other").expect("index should be valid");
+
+    assert_eq!(index.get_area("10-19").map(|area| area.get_name()), Some("Area"), "should get an area by number");
+    assert_eq!(index.get_category("11").map(|category| category.get_name()), Some("Category"), "should get a category by number");
+    assert_eq!(index.get_id("11.01").map(|id| id.get_name()), Some("Id"), "should get an id by number");
+
+    assert!(index.get_area("30-39").is_none(), "should not get a missing area");
+    assert!(index.get_category("21").is_none(), "should not get a missing category");
+    assert!(index.get_id("11.02").is_none(), "should not get a missing id");
+    assert!(index.get_id("11").is_none(), "should not get an id with an invalid number");
+
+    let id = index.get_id("11.01").expect("id should exist");
+
+    assert_eq!(
+        index.get_category(id.get_category()).map(|category| category.get_name()),
+        Some("Category"),
+        "should get a category by typed number"
+    );
+}
+
+#[test]
+fn test_rename() {
+    let mut index = Index::new("10-19 Area\n11 Category\n// Note\n11.01 Id\n- owner: Alice\n11.02 Other").expect("index should be valid");
+
+    assert!(index.rename("10-19", "New area").is_ok(), "should rename areas");
+    assert!(index.rename("11", "New category").is_ok(), "should rename categories");
+    assert!(index.rename("11.01", "New id").is_ok(), "should rename ids");
+    assert_eq!(
+        index.to_string(),
+        "10-19 New area\n11 New category\n// Note\n11.01 New id\n- owner: Alice\n11.02 Other",
+        "should keep metadata, comments, and other entries"
+    );
+
+    assert!(matches!(index.rename("11.03", "Missing"), Err(Error::NotFound(_))), "should fail for missing entries");
+    assert!(matches!(index.rename("11.02", "A/B"), Err(Error::InvalidTitle(_))), "should fail for invalid titles");
+    assert!(matches!(index.rename("11.02", ""), Err(Error::InvalidTitle(_))), "should fail for empty titles");
+    assert!(index.rename("Other", "Title").is_err(), "should fail for invalid numbers");
+}
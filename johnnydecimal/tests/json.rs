@@ -0,0 +1,40 @@
+//! Tests for the `json` feature.
+#![cfg(feature = "json")]
+
+use johnnydecimal::{Error, Index};
+use std::fs;
+
+#[test]
+fn test_json_round_trip() {
+    let source = fs::read_to_string("../tests/file/00.00 Index.txt").expect("index file should exist");
+    let index = Index::new(&source).expect("index should be valid");
+    let json = index.to_json();
+    let round_trip = Index::from_json(&json).expect("json should be valid");
+
+    assert_eq!(round_trip, index, "should round trip");
+    assert_eq!(round_trip.to_string(), index.to_string(), "should keep metadata and comments");
+
+    let value: serde_json::Value = serde_json::from_str(&json).expect("should export valid json");
+
+    assert_eq!(value["areas"][0]["area"], "10-19", "should export areas");
+    assert_eq!(value["areas"][0]["categories"][0]["category"], "11", "should nest categories in areas");
+    assert_eq!(value["areas"][0]["categories"][0]["ids"][0]["id"], "11.01", "should nest ids in categories");
+    assert_eq!(value["areas"][0]["categories"][0]["ids"][0]["metadata"]["location"], "Desk drawer", "should export metadata");
+}
+
+#[test]
+fn test_json_errors() {
+    let misplaced = Index::from_json(r#"{"areas":[{"area":"10-19","name":"Area","categories":[{"category":"21","name":"Category"}]}]}"#);
+    let duplicate = Index::from_json(r#"{"areas":[{"area":"10-19","name":"Area"},{"area":"10-19","name":"Again"}]}"#);
+    let invalid = Index::from_json("{\n  \"areas\": [1]\n}");
+
+    assert!(matches!(misplaced, Err(Error::Import { .. })), "should fail if nested in the wrong parent");
+    assert!(matches!(duplicate, Err(Error::Duplicate { .. })), "should fail on duplicates");
+    assert!(matches!(invalid, Err(Error::Import { line: Some(2), .. })), "should fail with the line of invalid json");
+    assert!(Index::from_json(r#"{"areas":[{"area":"10-19","name":"../Invalid"}]}"#).is_err(), "should fail on invalid titles");
+    assert!(
+        Index::from_json(r#"{"areas":[{"area":"10-19","name":"Area","owner":"Bob"}]}"#).is_err(),
+        "should fail on unknown fields"
+    );
+    assert_eq!(Index::from_json("{}").expect("empty index should be valid"), Index::default(), "should allow empty indexes");
+}
@@ -0,0 +1,61 @@
+//! Tests for `Index::merge`.
+
+use johnnydecimal::{Conflict, Index};
+
+fn merge(base: &str, ours: &str, theirs: &str) -> johnnydecimal::Merge {
+    let index = |str: &str| Index::new(str).expect("index should be valid");
+
+    Index::merge(&index(base), &index(ours), &index(theirs))
+}
+
+#[test]
+fn test_merge_clean() {
+    let base = "10-19 Area\n11 Category\n11.01 Id\n11.02 Removed\n- owner: Alice";
+    let ours = "10-19 Area\n- owner: Bob\n11 Category\n11.01 Retitled\n11.02 Removed\n- owner: Alice\n11.03 Ours";
+    let theirs = "10-19 Area\n11 Category\n11.01 Id\n12 Theirs\n20-29 New";
+    let merged = merge(base, ours, theirs);
+
+    assert!(!merged.has_conflicts(), "should merge changes to different entries");
+    assert_eq!(
+        merged.get_index().to_string(),
+        "10-19 Area\n- owner: Bob\n11 Category\n11.01 Retitled\n11.03 Ours\n12 Theirs\n20-29 New",
+        "should take changes from both sides"
+    );
+
+    let same = merge(base, ours, ours);
+
+    assert!(!same.has_conflicts(), "should merge the same change on both sides");
+    assert_eq!(
+        same.get_index().to_string(),
+        Index::new(ours).expect("index should be valid").to_string(),
+        "should keep the same change"
+    );
+}
+
+#[test]
+fn test_merge_conflicts() {
+    let base = "10-19 Area\n11 Category\n11.01 Id\n11.02 Kept\n12 Removed";
+    let ours = "10-19 Area\n11 Category\n11.01 Ours\n11.02 Kept\n11.03 Claimed\n12 Removed\n12.01 New";
+    let theirs = "10-19 Area\n11 Category\n11.01 Theirs\n11.03 Other";
+    let merged = merge(base, ours, theirs);
+    let conflicts = merged.get_conflicts();
+
+    assert!(matches!(conflicts[0], Conflict::Changed { .. }), "should conflict on ids retitled differently");
+    assert!(matches!(conflicts[1], Conflict::Claimed { .. }), "should conflict on numbers claimed twice");
+    assert!(matches!(conflicts[2], Conflict::Orphaned { .. }), "should conflict on ids added under removed categories");
+    assert_eq!(conflicts.len(), 3, "should remove entries that are unchanged on the other side");
+
+    let text = merged.get_index().to_string();
+
+    assert!(
+        text.contains("// Conflict: `11.01 Id` was changed to `11.01 Ours` in ours and `11.01 Theirs` in theirs\n11.01 Ours"),
+        "should keep ours with a comment"
+    );
+    assert!(text.contains("12 Removed\n12.01 New"), "should keep the parent of orphans");
+    assert!(!text.contains("11.02"), "should remove entries removed on one side");
+    assert!(Index::new(&text).is_ok(), "should always be a valid index");
+
+    let removed = merge(base, "10-19 Area\n11 Category\n11.01 Id\n12 Removed", "10-19 Area\n11 Category\n11.01 Id\n11.02 Changed\n12 Removed");
+
+    assert!(matches!(removed.get_conflicts()[..], [Conflict::Removed { .. }]), "should conflict on entries removed and changed");
+}
@@ -0,0 +1,67 @@
+//! Tests for `AreaNumber`, `CategoryNumber`, and `IdNumber`.
+
+use johnnydecimal::{AreaNumber, Category, CategoryNumber, Id, IdNumber};
+use std::collections::HashSet;
+
+#[test]
+fn test_area_number() {
+    let area: AreaNumber = "30-39".parse().expect("area number should be valid");
+
+    assert_eq!(area.to_string(), "30-39", "should display as a0-a9");
+    assert_eq!(u8::from(area), 3, "should be stored as its digit");
+    assert_eq!(area.next(), "40-49".parse().ok(), "should get next area");
+    assert_eq!(area.previous(), "20-29".parse().ok(), "should get previous area");
+    assert_eq!(
+        area.children().map(|category| category.to_string()).collect::<Vec<String>>().join(" "),
+        "30 31 32 33 34 35 36 37 38 39",
+        "should get categories"
+    );
+    assert!("90-99".parse::<AreaNumber>().expect("area number should be valid").next().is_none(), "should not go past 90-99");
+    assert!("00-09".parse::<AreaNumber>().expect("area number should be valid").previous().is_none(), "should not go before 00-09");
+    assert!(AreaNumber::try_from(10).is_err(), "should fail if digit is not in [0..9]");
+
+    for invalid in ["10-29", "11-19", "10_19", "10-19 Area", "a0-a9", ""] {
+        assert!(invalid.parse::<AreaNumber>().is_err(), "`{invalid}` should not be an area number");
+    }
+}
+
+#[test]
+fn test_category_number() {
+    let category: CategoryNumber = "09".parse().expect("category number should be valid");
+
+    assert_eq!(category.to_string(), "09", "should display with two digits");
+    assert_eq!(category.get_area(), "00-09", "should get area");
+    assert_eq!(category.next(), "10".parse().ok(), "next category may be in the next area");
+    assert_eq!(category.children().nth(5), "09.05".parse().ok(), "should get ids");
+    assert_eq!(CategoryNumber::try_from(99).ok().and_then(CategoryNumber::next), None, "should not go past 99");
+    assert!("9".parse::<CategoryNumber>().is_err(), "should need two digits");
+    assert!("1a".parse::<CategoryNumber>().is_err(), "should need digits");
+}
+
+#[test]
+fn test_id_number() {
+    let id: IdNumber = "11.99".parse().expect("id number should be valid");
+
+    assert_eq!(id, "11.99", "should compare with written form");
+    assert_eq!(id.get_area(), "10-19", "should get area");
+    assert_eq!(id.get_category(), "11", "should get category");
+    assert!(id.next().is_none(), "should not go past ac.99");
+    assert_eq!(id.previous(), IdNumber::try_from((11, 98)).ok(), "should get previous id");
+    assert!(IdNumber::try_from((11, 100)).is_err(), "should fail if id is not in [00..99]");
+    assert!("11.1".parse::<IdNumber>().is_err(), "should need two digits");
+    assert!("11-01".parse::<IdNumber>().is_err(), "should need a decimal separator");
+}
+
+#[test]
+fn test_numbers_of_entries() {
+    let id = Id::new("25.03 Id").expect("id should be valid");
+    let category = Category::new("25 Category").expect("category should be valid");
+
+    assert_eq!(id.get_category(), category.get_category(), "should compare typed numbers");
+    assert_eq!(id.get_id().get_category(), category.get_category(), "should compute parent from id number");
+    assert!(id.get_id() > "25.02".parse().expect("id number should be valid"), "should order numerically");
+
+    let set: HashSet<CategoryNumber> = [id.get_category(), category.get_category()].into_iter().collect();
+
+    assert_eq!(set.len(), 1, "should hash equal numbers the same");
+}
@@ -0,0 +1,56 @@
+//! Tests for the `opml` feature.
+#![cfg(feature = "opml")]
+
+use johnnydecimal::{Error, Index};
+use std::fs;
+
+#[test]
+fn test_opml_round_trip() {
+    let source = fs::read_to_string("../tests/file/00.00 Index.txt").expect("index file should exist");
+    let index = Index::new(&source).expect("index should be valid");
+    let opml = index.to_opml();
+    let round_trip = Index::from_opml(&opml).expect("opml should be valid");
+
+    assert!(opml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\" "), "should be an opml document");
+    assert!(round_trip.get_areas().eq(index.get_areas()), "should round trip areas");
+    assert!(round_trip.get_categories().eq(index.get_categories()), "should round trip categories");
+    assert_eq!(round_trip.to_opml(), opml, "should round trip metadata");
+}
+
+#[test]
+fn test_opml_attributes() {
+    let index = Index::new("10-19 Area\n- owner: Alice & Bob\n- text: Hidden\n- due date: Soon\n11 Category").expect("index should be valid");
+    let opml = index.to_opml();
+
+    assert!(
+        opml.contains("    <outline text=\"10-19 Area\" owner=\"Alice &amp; Bob\" jd:text=\"Hidden\" jd:due_x0020_date=\"Soon\">\n      <outline text=\"11 Category\"/>\n    </outline>\n"),
+        "should nest outlines with metadata as attributes, escaping keys that aren't attribute names"
+    );
+    assert_eq!(Index::from_opml(&opml).expect("opml should be valid"), index, "should round trip escaped keys");
+    assert!(
+        Index::from_opml(r#"<opml><body><outline text="10-19 Area" jd:due_x00_date="Soon"/></body></opml>"#).is_err(),
+        "should fail on invalid escapes"
+    );
+
+    let imported = Index::from_opml(r#"<opml version="2.0"><body><outline text="10-19 Area" _note="Notes"/></body></opml>"#).expect("opml should be valid");
+
+    assert_eq!(
+        imported.get_areas().next().expect("should have an area").get_metadata().get("_note"),
+        Some("Notes"),
+        "should read attributes as metadata"
+    );
+}
+
+#[test]
+fn test_opml_errors() {
+    let misplaced = Index::from_opml("<opml>\n<body>\n<outline text=\"10-19 Area\">\n<outline text=\"21 Category\"/>\n</outline>\n</body>\n</opml>");
+    let duplicate = Index::from_opml(r#"<opml><body><outline text="10-19 Area"/><outline text="10-19 Again"/></body></opml>"#);
+    let deep = Index::from_opml(r#"<opml><body><outline text="10-19 A"><outline text="11 C"><outline text="11.01 I"><outline text="Deep"/></outline></outline></outline></body></opml>"#);
+
+    assert!(matches!(misplaced, Err(Error::Import { line: Some(4), .. })), "should fail with the line of misplaced outlines");
+    assert!(matches!(duplicate, Err(Error::Duplicate { .. })), "should fail on duplicates");
+    assert!(matches!(deep, Err(Error::Import { .. })), "should fail on outlines deeper than ids");
+    assert!(Index::from_opml(r#"<opml><body><outline title="10-19 Area"/></body></opml>"#).is_err(), "should require text");
+    assert!(Index::from_opml("<html></html>").is_err(), "should require an opml root");
+    assert!(Index::from_opml("<opml><body>").is_err(), "should fail on invalid xml");
+}
@@ -0,0 +1,54 @@
+//! Tests for the Markdown and HTML renderers.
+
+use johnnydecimal::{Index, RenderOptions};
+
+const SOURCE: &str = "// Work\n10-19 Area\n   11 Category // Inline\n      11.01 Id\n      - owner: Alice\n   12 Empty\n20-29 Second\n/* The end */";
+
+#[test]
+fn test_markdown_lists() {
+    let index = Index::new(SOURCE).expect("index should be valid");
+
+    assert_eq!(
+        index.to_markdown(&RenderOptions::new()),
+        "- 10-19 Area\n  - 11 Category\n    - 11.01 Id\n  - 12 Empty\n- 20-29 Second\n",
+        "should render nested lists"
+    );
+
+    assert_eq!(
+        index.to_markdown(&RenderOptions::new().with_metadata(true).with_comments(true)),
+        "- 10-19 Area\n  - *Work*\n  - 11 Category\n    - *Inline*\n    - 11.01 Id\n      - **owner**: Alice\n  - 12 Empty\n- 20-29 Second\n*The end*\n",
+        "should render metadata and comments"
+    );
+}
+
+#[test]
+fn test_markdown_headings() {
+    let index = Index::new(SOURCE).expect("index should be valid");
+    let markdown = index.to_markdown(&RenderOptions::new().with_headings(true).with_metadata(true));
+
+    assert_eq!(
+        markdown, "# 10-19 Area\n\n## 11 Category\n\n### 11.01 Id\n\n- **owner**: Alice\n\n## 12 Empty\n\n# 20-29 Second\n",
+        "should render headings"
+    );
+
+    let escaped = Index::new("10-19 Area\n11 Under_score").expect("index should be valid");
+
+    assert!(escaped.to_markdown(&RenderOptions::new()).contains("Under\\_score"), "should escape Markdown");
+}
+
+#[test]
+fn test_html() {
+    let index = Index::new(SOURCE).expect("index should be valid");
+    let html = index.to_html(&RenderOptions::new().with_title("Team & Co").with_metadata(true).with_comments(true));
+
+    assert!(html.starts_with("<!DOCTYPE html>\n"), "should be a standalone page");
+    assert!(html.contains("<title>Team &amp; Co</title>"), "should escape the title");
+    assert!(html.contains(r##"<li id="11.01"><a href="#11.01">11.01</a> Id"##), "should have an anchor per id");
+    assert!(html.contains("<dt>owner</dt><dd>Alice</dd>"), "should render metadata");
+    assert!(html.contains(r#"<p class="comment">Inline</p>"#), "should render comments");
+    assert!(html.contains(r##"<li id="12"><a href="#12">12</a> Empty</li>"##), "should close childless entries on the same line");
+
+    let plain = index.to_html(&RenderOptions::new());
+
+    assert!(!plain.contains("<dl>") && !plain.contains("comment"), "should leave out metadata and comments by default");
+}
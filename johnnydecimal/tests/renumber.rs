@@ -0,0 +1,80 @@
+//! Tests for renumbering ids in an `Index`.
+
+use johnnydecimal::{Category, Error, Id, Index};
+
+#[test]
+fn test_move_id() {
+    let mut index = Index::new("10-19 Area\n11 Category\n// Note\n11.03 Id\n- owner: Alice\n12 Other\n12.01 First\n13 Empty").expect("index should be valid");
+    let id = index.get_id("11.03").expect("id should exist").clone();
+    let other = index.get_category("12").expect("category should exist").clone();
+
+    assert_eq!(
+        index.move_id(&id, &other).map(|id| id.get_id()),
+        Ok("12.02".parse().expect("should be valid")),
+        "should use the next free id"
+    );
+    assert!(index.get_id("11.03").is_none(), "should remove the old number");
+    assert_eq!(
+        index.to_string(),
+        "10-19 Area\n11 Category\n12 Other\n12.01 First\n// Note\n12.02 Id\n- owner: Alice\n13 Empty",
+        "should keep the title, metadata, and comments"
+    );
+
+    assert!(matches!(index.move_id(&id, &other), Err(Error::NotFound(_))), "should fail for missing ids");
+
+    let mut full = Index::new(&(1..=99).fold("10-19 Area\n11 Category\n11.01 Id\n12 Full".to_string(), |str, i| format!("{str}\n12.{i:02} Id"))).expect("index should be valid");
+    let id = full.get_id("11.01").expect("id should exist").clone();
+    let category = full.get_category("12").expect("category should exist").clone();
+
+    assert!(matches!(full.move_id(&id, &category), Err(Error::Full(_))), "should fail for full categories");
+    assert!(full.get_id("11.01").is_some(), "should not remove ids that can't be moved");
+}
+
+#[test]
+fn test_insert_id_at() {
+    let mut index = Index::new("10-19 Area\n11 Category\n11.01 First\n11.02 Second\n11.04 Fourth\n- owner: Alice").expect("index should be valid");
+    let shifted = index.insert_id_at(&Id::new("11.01 New").expect("id should be valid")).expect("should insert");
+    let shifted: Vec<String> = shifted.iter().map(|(from, to)| format!("{from} -> {to}")).collect();
+
+    assert_eq!(shifted, ["11.02 Second -> 11.03 Second", "11.01 First -> 11.02 First"], "should shift from the highest number down");
+    assert_eq!(
+        index.to_string(),
+        "10-19 Area\n11 Category\n11.01 New\n11.02 First\n11.03 Second\n11.04 Fourth\n- owner: Alice",
+        "should stop shifting at the first free number"
+    );
+
+    let free = index.insert_id_at(&Id::new("11.10 Free").expect("id should be valid")).expect("should insert");
+
+    assert!(free.is_empty(), "should not shift anything into a free number");
+    assert!(index.insert_id_at(&Id::new("12.01 Orphan").expect("id should be valid")).is_err(), "should fail for orphans");
+
+    let mut full = Index::new(&(90..=99).fold("10-19 Area\n11 Category".to_string(), |str, i| format!("{str}\n11.{i} Id"))).expect("index should be valid");
+    let before = full.to_string();
+
+    assert_eq!(
+        full.insert_id_at(&Id::new("11.90 New").expect("id should be valid")).map(|shifted| shifted.len()),
+        Err(Error::Overflow("11.99".to_string())),
+        "should fail if shifting past .99"
+    );
+    assert_eq!(full.to_string(), before, "should not change anything if shifting past .99");
+}
+
+#[test]
+fn test_insert_category_at() {
+    let mut index = Index::new("10-19 Area\n11 First\n11.01 Id\n12 Second\n12.05 Other\n14 Fourth").expect("index should be valid");
+    let shifted = index.insert_category_at(&Category::new("11 New").expect("category should be valid")).expect("should insert");
+
+    assert_eq!(shifted.len(), 2, "should shift categories up to the first free number");
+    assert_eq!(
+        index.to_string(),
+        "10-19 Area\n11 New\n12 First\n12.01 Id\n13 Second\n13.05 Other\n14 Fourth",
+        "should renumber the ids of shifted categories"
+    );
+
+    let mut full = Index::new("10-19 Area\n18 Eight\n19 Nine\n20-29 Next").expect("index should be valid");
+
+    assert!(
+        matches!(full.insert_category_at(&Category::new("18 New").expect("category should be valid")), Err(Error::Overflow(_))),
+        "should fail if shifting past x9 instead of into the next area"
+    );
+}
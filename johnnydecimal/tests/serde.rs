@@ -0,0 +1,80 @@
+//! Tests for the `serde` feature.
+#![cfg(feature = "serde")]
+
+use johnnydecimal::{Area, Category, Id, IdNumber, Index};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_serialize_entries() {
+    let mut id = Id::new("11.01 Id").expect("id should be valid");
+
+    id.set_metadata("owner", "Alice").expect("metadata should be valid");
+
+    assert_eq!(
+        serde_json::to_string(&id).expect("id should serialize"),
+        r#"{"id":"11.01","name":"Id","metadata":{"owner":"Alice"}}"#,
+        "should serialize as a structured object"
+    );
+
+    let from_object: Id = serde_json::from_str(r#"{"id":"11.01","name":"Id","metadata":{"owner":"Alice"}}"#).expect("object should deserialize");
+    let from_string: Category = serde_json::from_str(r#""11 Category""#).expect("string should deserialize");
+
+    assert_eq!(from_object.get_metadata().get("owner"), Some("Alice"), "should read metadata");
+    assert_eq!(from_object.get_category(), "11", "should derive category from id");
+    assert_eq!(from_string.get_name(), "Category", "should read canonical string");
+    assert_eq!(
+        serde_json::to_string(&from_object.get_id()).expect("number should serialize"),
+        r#""11.01""#,
+        "should serialize numbers as strings"
+    );
+    assert_eq!(
+        serde_json::from_str::<IdNumber>(r#""11.01""#).ok(),
+        Some(from_object.get_id()),
+        "should deserialize numbers from strings"
+    );
+
+    assert!(serde_json::from_str::<Area>(r#""10-19 ../Invalid""#).is_err(), "should validate strings");
+    assert!(serde_json::from_str::<Area>(r#"{"area":"10-19","name":"../Invalid"}"#).is_err(), "should validate titles");
+    assert!(serde_json::from_str::<Area>(r#"{"area":"10-29","name":"Area"}"#).is_err(), "should validate numbers");
+    assert!(
+        serde_json::from_str::<Area>(r#"{"area":"10-19","name":"Area","comments":{"above":["Not a comment"]}}"#).is_err(),
+        "should validate comments"
+    );
+}
+
+#[test]
+fn test_as_string() {
+    #[derive(Serialize, Deserialize)]
+    struct Cache {
+        #[serde(with = "johnnydecimal::as_string")]
+        area: Area,
+    }
+
+    let cache: Cache = serde_json::from_str(r#"{"area":"10-19 Area"}"#).expect("cache should deserialize");
+
+    assert_eq!(
+        serde_json::to_string(&cache).expect("cache should serialize"),
+        r#"{"area":"10-19 Area"}"#,
+        "should round trip as a string"
+    );
+}
+
+#[test]
+fn test_serialize_index() {
+    let index = Index::new("// Start\n10-19 Area\n   11 Category // Inline\n      11.01 Id\n      - owner: Alice\n// End").expect("index should be valid");
+    let json = serde_json::to_string(&index).expect("index should serialize");
+    let round_trip: Index = serde_json::from_str(&json).expect("index should deserialize");
+
+    assert_eq!(round_trip, index, "should round trip");
+    assert_eq!(round_trip.to_string(), index.to_string(), "should keep metadata and comments");
+
+    let mixed: Index = serde_json::from_str(r#"{"areas":["10-19 Area"],"categories":[{"category":"11","name":"Category"}]}"#).expect("mixed forms should deserialize");
+
+    assert_eq!(mixed.get_categories().len(), 1, "should read both forms");
+
+    let orphan = serde_json::from_str::<Index>(r#"{"areas":[],"categories":["11 Category"]}"#);
+    let duplicate = serde_json::from_str::<Index>(r#"{"areas":["10-19 Area","10-19 Again"]}"#);
+
+    assert!(orphan.is_err_and(|error| error.to_string().contains("no parent")), "should fail on orphans");
+    assert!(duplicate.is_err_and(|error| error.to_string().contains("Duplicate")), "should fail on duplicates");
+}
@@ -0,0 +1,66 @@
+//! Tests for CSV and TSV import and export.
+
+use johnnydecimal::{Error, Index};
+use std::fs;
+
+#[test]
+fn test_table_round_trip() {
+    let source = fs::read_to_string("../tests/file/00.00 Index.txt").expect("index file should exist");
+    let index = Index::new(&source).expect("index should be valid");
+
+    let csv = Index::from_csv(&index.to_csv()).expect("csv should be valid");
+    let tsv = Index::from_tsv(&index.to_tsv()).expect("tsv should be valid");
+
+    assert!(csv.get_areas().eq(index.get_areas()), "should round trip areas through csv");
+    assert!(csv.get_categories().eq(index.get_categories()), "should round trip categories through csv");
+    assert_eq!(csv.get_ids().len(), index.get_ids().len(), "should round trip ids through csv");
+    assert_eq!(tsv.to_csv(), index.to_csv(), "should round trip through tsv");
+}
+
+#[test]
+fn test_table_columns() {
+    let index = Index::new("10-19 Area, with comma\n11 Category\n- note: Says \"hi\"\n11.01 Id\n- owner: Alice").expect("index should be valid");
+    let csv = index.to_csv();
+
+    assert!(csv.starts_with("kind,area,category,id,title,note,owner\n"), "should add a column per metadata key");
+    assert!(csv.contains("area,10-19,,,\"Area, with comma\",,\n"), "should quote cells with the separator");
+    assert!(csv.contains("category,10-19,11,,Category,\"Says \"\"hi\"\"\",\n"), "should double quotes");
+    assert!(index.to_tsv().contains("area\t10-19\t\t\tArea, with comma\t\t\n"), "should not quote commas in tsv");
+
+    let sparse = Index::from_csv("kind,area,category,id,title,owner\nid,,,11.01,Id,Bob\ncategory,,11,,Category\narea,10-19,,,Area").expect("csv should be valid");
+
+    assert_eq!(
+        sparse.get_ids().next().expect("should have an id").get_metadata().get("owner"),
+        Some("Bob"),
+        "should read metadata columns"
+    );
+    assert!(
+        sparse.get_categories().next().expect("should have a category").get_metadata().is_empty(),
+        "should skip empty metadata cells"
+    );
+}
+
+#[test]
+fn test_table_errors() {
+    let header = "kind,area,category,id,title\n";
+    let row = |csv: &str| match Index::from_csv(&format!("{header}{csv}")) {
+        Err(Error::Import { line, .. }) => line,
+        _ => None,
+    };
+
+    assert_eq!(row("area,10-19,,,Area\narea,10-19,,,Again"), Some(3), "should report duplicates by row");
+    assert_eq!(row("area,10-19,,,Area\nid,,,12.01,Orphan"), Some(3), "should report orphans by row");
+    assert_eq!(row("area,10-19,,,Area\nfolder,,,,Nope"), Some(3), "should report unknown kinds by row");
+    assert_eq!(row("area,10-19,,,Area\ncategory,20-29,11,,Category"), Some(3), "should report mismatched numbers by row");
+    assert_eq!(row("area,10-19,,,../Invalid"), Some(2), "should report invalid titles by row");
+    assert_eq!(row("area,10-19,,,\"Area"), Some(2), "should report unclosed quotes by row");
+    assert!(Index::from_csv("kind,title\narea,Area").is_err(), "should require the entry columns");
+    assert!(
+        matches!(
+            Index::from_csv("kind,area,category,id,title,owner,owner\narea,10-19,,,Area,Alice,Bob"),
+            Err(Error::Import { line: Some(1), .. })
+        ),
+        "should report duplicate metadata columns in the header"
+    );
+    assert_eq!(Index::from_csv("").expect("empty csv should be valid"), Index::default(), "should allow empty tables");
+}
@@ -0,0 +1,69 @@
+//! Tests for `Index::validate`.
+
+use johnnydecimal::{Error, Index, Severity};
+
+#[test]
+fn test_validate() {
+    let validation = Index::validate(
+        "10-19 Area\n   11 Category\n      11.01 Id\n      11.01 Duplicate\n   13 Third\n20-29 Second\n   12 Late\n   22 Category\nNot an entry\n   31.01 Orphan\n      - owner: Nobody\n   22.01 Id",
+    );
+
+    let errors: Vec<(Severity, Option<usize>)> = validation.get_diagnostics().iter().map(|d| (d.get_severity(), d.get_line())).collect();
+
+    assert_eq!(
+        errors,
+        vec![(Severity::Error, Some(4)), (Severity::Warning, Some(7)), (Severity::Error, Some(9)), (Severity::Error, Some(10))],
+        "should collect every problem in order of lines"
+    );
+
+    assert!(
+        matches!(validation.get_diagnostics()[0].get_error(), Error::Duplicate { number, .. } if number == "11.01"),
+        "should report duplicates"
+    );
+
+    assert!(
+        matches!(validation.get_diagnostics()[1].get_error(), Error::OutOfOrder { number, previous, .. } if number == "12" && previous == "20-29"),
+        "should report entries out of order"
+    );
+
+    assert!(matches!(validation.get_diagnostics()[3].get_error(), Error::Orphan { .. }), "should report orphans");
+    assert!(validation.has_errors(), "should have errors");
+
+    let index = validation.into_index();
+
+    assert_eq!(index.get_areas().len(), 2, "should keep valid areas");
+    assert_eq!(index.get_categories().len(), 4, "should keep valid and out of order categories");
+    assert_eq!(index.get_ids().len(), 2, "should keep valid ids");
+    assert_eq!(index.get_ids().next().expect("should have an id").get_name(), "Id", "should keep the first of duplicates");
+}
+
+#[test]
+fn test_validate_warnings() {
+    let str = "20-29 Second\n10-19 First\n   11 Category";
+    let validation = Index::validate(str);
+
+    assert!(!validation.has_errors(), "warnings should not be errors");
+    assert_eq!(
+        validation.get_diagnostics()[0].to_string(),
+        "warning: Line 2: `10-19` should come before `20-29`",
+        "should display severity"
+    );
+    assert!(Index::new(str).is_ok(), "Index::new should allow warnings");
+
+    let invalid = Index::validate("10-19 Area\n   11 Category\n   12 ../Invalid");
+
+    assert!(
+        matches!(invalid.get_diagnostics()[0].get_error(), Error::Parse(error) if error.get_line() == 3),
+        "should report invalid titles"
+    );
+    assert_eq!(
+        Index::new("10-19 Area\n   11 Category\n   12 ../Invalid").err().as_ref(),
+        Some(invalid.get_diagnostics()[0].get_error()),
+        "Index::new should return the first error"
+    );
+
+    let unclosed = Index::validate("10-19 Area\n/* Never closed");
+
+    assert_eq!(unclosed.get_diagnostics().len(), 1, "should report unclosed comments");
+    assert!(unclosed.get_index().get_areas().next().is_none(), "should not read entries if comments aren't closed");
+}